@@ -0,0 +1,78 @@
+use serde::{de, Deserialize, Deserializer, Serialize};
+use specs::Entity;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlaybackMode {
+    /// Starts over from the first frame after the last one.
+    Loop,
+    /// Stays on the last frame once it has been shown for its duration.
+    Once,
+}
+
+impl Default for PlaybackMode {
+    fn default() -> Self {
+        PlaybackMode::Loop
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AnimationFrame {
+    /// Name of the frame in the atlas of the sprite's image.
+    pub frame: String,
+    /// How long the frame is shown, in seconds. Loading fails for durations that aren't positive.
+    #[serde(deserialize_with = "positive_duration")]
+    pub duration: f64,
+}
+
+fn positive_duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    let duration = f64::deserialize(deserializer)?;
+    if duration > 0.0 {
+        Ok(duration)
+    } else {
+        Err(de::Error::custom(format!(
+            "frame duration must be positive, got {}",
+            duration
+        )))
+    }
+}
+
+/// A named sequence of atlas frames, such as "walk_north".
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct AnimationClip {
+    pub frames: Vec<AnimationFrame>,
+    #[serde(default)]
+    pub mode: PlaybackMode,
+}
+
+impl AnimationClip {
+    /// A clip showing each frame for the same duration.
+    pub fn uniform(frames: &[&str], duration: f64, mode: PlaybackMode) -> AnimationClip {
+        AnimationClip {
+            frames: frames
+                .iter()
+                .map(|frame| AnimationFrame {
+                    frame: frame.to_string(),
+                    duration,
+                })
+                .collect(),
+            mode,
+        }
+    }
+
+    /// Total duration in seconds, counting frames with a negative duration as having none.
+    pub fn duration(&self) -> f64 {
+        self.frames
+            .iter()
+            .map(|frame| frame.duration.max(0.0))
+            .sum()
+    }
+}
+
+/// Written to the `EventChannel<AnimationEvent>` resource by the animation system. Gameplay
+/// systems read them by registering a reader in their `setup`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnimationEvent {
+    /// The clip showed its last frame for its full duration. Looping clips report this every time
+    /// they start over.
+    ClipFinished { entity: Entity, clip: String },
+}
@@ -0,0 +1,302 @@
+use find_folder::Search;
+use ggez::filesystem;
+use ggez::graphics;
+use ggez::Context;
+use ggez::GameError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::path::PathBuf;
+
+const ASSETS_FOLDER: &str = "assets";
+const PLACEHOLDER_SIZE: u16 = 16;
+const ATLAS_EXTENSION: &str = "ron";
+/// The key the placeholder texture is known by, never a real file's path.
+pub const PLACEHOLDER_PATH: &str = "/placeholder";
+
+#[derive(Debug)]
+pub enum AssetError {
+    /// Assets can only be loaded by game states with a window.
+    NoContext,
+    Load {
+        path: String,
+        error: GameError,
+    },
+    /// The atlas file next to an image couldn't be read.
+    Atlas {
+        path: String,
+        error: String,
+    },
+    MissingFrame {
+        path: String,
+        frame: String,
+    },
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetError::NoContext => write!(f, "Cannot load assets without a window"),
+            AssetError::Load { path, error } => write!(f, "Failed loading {}: {}", path, error),
+            AssetError::Atlas { path, error } => {
+                write!(f, "Failed loading atlas {}: {}", path, error)
+            }
+            AssetError::MissingFrame { path, frame } => {
+                write!(f, "No frame named {} in atlas of {}", frame, path)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+/// Finds the assets folder near the working directory or the executable, so assets resolve the
+/// same way whether the game is started through cargo or from its install location.
+pub fn find_assets_folder() -> Option<PathBuf> {
+    Search::ParentsThenKids(3, 3)
+        .for_folder(ASSETS_FOLDER)
+        .ok()
+        .or_else(|| {
+            let executable_folder = env::current_exe().ok()?.parent()?.to_path_buf();
+            Search::Parents(3)
+                .of(executable_folder)
+                .for_folder(ASSETS_FOLDER)
+                .ok()
+        })
+}
+
+/// Turns a path into the virtual path assets are keyed by: forward slashes, rooted in the assets
+/// folder and without `.` or empty segments, so `"\\sprite.png"` and `"./sprite.png"` are the same.
+/// `..` removes the segment before it and can't leave the assets folder, like `..` at a file
+/// system root.
+pub fn normalize_path(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split(|c| c == '/' || c == '\\') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    format!("/{}", segments.join("/"))
+}
+
+/// The atlas file describing the frames of an image, next to it with the same name, such as
+/// `/characters.ron` for `/characters.png`.
+pub fn atlas_path(image_path: &str) -> String {
+    let image_path = normalize_path(image_path);
+    let file_start = image_path.rfind('/').map(|index| index + 1).unwrap_or(0);
+    let stem = match image_path[file_start..].rfind('.') {
+        Some(index) => &image_path[..file_start + index],
+        None => &image_path[..],
+    };
+    format!("{}.{}", stem, ATLAS_EXTENSION)
+}
+
+/// A region of an image in pixels, from its top left corner.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Frame {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl Frame {
+    /// The frame as the fraction of the image `DrawParam.src` expects.
+    pub fn to_src(&self, width: u16, height: u16) -> graphics::Rect {
+        let (width, height) = (f32::from(width), f32::from(height));
+        graphics::Rect::new(
+            self.x as f32 / width,
+            self.y as f32 / height,
+            self.w as f32 / width,
+            self.h as f32 / height,
+        )
+    }
+}
+
+/// Named frames of a sprite sheet, so a single image can hold many sprites.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TextureAtlas {
+    pub frames: HashMap<String, Frame>,
+}
+
+/// Loads each image once and hands out clones sharing the same texture.
+#[derive(Default)]
+pub struct AssetCache {
+    pub root: Option<PathBuf>,
+    images: HashMap<String, graphics::Image>,
+    /// Atlases by the path of the image they describe.
+    atlases: HashMap<String, TextureAtlas>,
+    placeholder: Option<graphics::Image>,
+}
+
+impl AssetCache {
+    pub fn new(root: Option<PathBuf>) -> AssetCache {
+        AssetCache {
+            root,
+            images: HashMap::new(),
+            atlases: HashMap::new(),
+            placeholder: None,
+        }
+    }
+
+    pub fn image(
+        &mut self,
+        context: &mut Context,
+        path: &str,
+    ) -> Result<graphics::Image, AssetError> {
+        let path = normalize_path(path);
+        if let Some(image) = self.images.get(&path) {
+            return Ok(image.clone());
+        }
+        let image = load_image(context, &path)?;
+        self.images.insert(path, image.clone());
+        Ok(image)
+    }
+
+    /// The atlas next to the image, loaded once.
+    pub fn atlas(
+        &mut self,
+        context: &mut Context,
+        image_path: &str,
+    ) -> Result<&TextureAtlas, AssetError> {
+        let image_path = normalize_path(image_path);
+        if !self.atlases.contains_key(&image_path) {
+            let atlas = load_atlas(context, &atlas_path(&image_path))?;
+            self.atlases.insert(image_path.clone(), atlas);
+        }
+        Ok(&self.atlases[&image_path])
+    }
+
+    /// Where the named frame is in the image, as the fraction `DrawParam.src` expects.
+    pub fn frame(
+        &mut self,
+        context: &mut Context,
+        image_path: &str,
+        frame: &str,
+    ) -> Result<graphics::Rect, AssetError> {
+        let image = self.image(context, image_path)?;
+        let atlas = self.atlas(context, image_path)?;
+        let found = atlas
+            .frames
+            .get(frame)
+            .ok_or_else(|| AssetError::MissingFrame {
+                path: normalize_path(image_path),
+                frame: frame.to_string(),
+            })?;
+        Ok(found.to_src(image.width(), image.height()))
+    }
+
+    /// Like `frame`, but only for images and atlases that are already loaded, so it doesn't need a
+    /// context.
+    pub fn loaded_frame(&self, image_path: &str, frame: &str) -> Option<graphics::Rect> {
+        let image_path = normalize_path(image_path);
+        let image = self.images.get(&image_path)?;
+        let found = self.atlases.get(&image_path)?.frames.get(frame)?;
+        Some(found.to_src(image.width(), image.height()))
+    }
+
+    /// A magenta and black checkerboard to draw in place of images that failed to load.
+    pub fn placeholder(&mut self, context: &mut Context) -> Result<graphics::Image, AssetError> {
+        if let Some(placeholder) = &self.placeholder {
+            return Ok(placeholder.clone());
+        }
+        let size = PLACEHOLDER_SIZE as usize;
+        let mut rgba = Vec::with_capacity(size * size * 4);
+        for y in 0..size {
+            for x in 0..size {
+                let magenta = (x / 4 + y / 4) % 2 == 0;
+                if magenta {
+                    rgba.extend_from_slice(&[255, 0, 255, 255]);
+                } else {
+                    rgba.extend_from_slice(&[0, 0, 0, 255]);
+                }
+            }
+        }
+        let placeholder =
+            graphics::Image::from_rgba8(context, PLACEHOLDER_SIZE, PLACEHOLDER_SIZE, &rgba)
+                .map_err(|error| AssetError::Load {
+                    path: PLACEHOLDER_PATH.to_string(),
+                    error,
+                })?;
+        self.placeholder = Some(placeholder.clone());
+        Ok(placeholder)
+    }
+
+    /// Loads the image again from disk, replacing the cached one. The cached image is kept if
+    /// loading fails.
+    pub fn reload(
+        &mut self,
+        context: &mut Context,
+        path: &str,
+    ) -> Result<graphics::Image, AssetError> {
+        let path = normalize_path(path);
+        let image = load_image(context, &path)?;
+        self.images.insert(path, image.clone());
+        Ok(image)
+    }
+
+    /// Loads the atlas again from disk, returning the path of the image it describes. Does nothing
+    /// for atlases that haven't been loaded.
+    pub fn reload_atlas(
+        &mut self,
+        context: &mut Context,
+        path: &str,
+    ) -> Option<Result<String, AssetError>> {
+        let path = normalize_path(path);
+        let image_path = self
+            .atlases
+            .keys()
+            .find(|image_path| atlas_path(image_path) == path)?
+            .clone();
+        Some(load_atlas(context, &path).map(|atlas| {
+            self.atlases.insert(image_path.clone(), atlas);
+            image_path
+        }))
+    }
+
+    pub fn is_loaded(&self, path: &str) -> bool {
+        self.images.contains_key(&normalize_path(path))
+    }
+
+    pub fn is_atlas_loaded(&self, image_path: &str) -> bool {
+        self.atlases.contains_key(&normalize_path(image_path))
+    }
+
+    /// Uses the atlas for the image instead of the one next to it, until that one is reloaded.
+    pub fn insert_atlas(&mut self, image_path: &str, atlas: TextureAtlas) {
+        self.atlases.insert(normalize_path(image_path), atlas);
+    }
+}
+
+fn load_image(context: &mut Context, path: &str) -> Result<graphics::Image, AssetError> {
+    graphics::Image::new(context, path).map_err(|error| AssetError::Load {
+        path: path.to_string(),
+        error,
+    })
+}
+
+fn load_atlas(context: &mut Context, path: &str) -> Result<TextureAtlas, AssetError> {
+    let file = filesystem::open(context, path).map_err(|error| AssetError::Atlas {
+        path: path.to_string(),
+        error: error.to_string(),
+    })?;
+    ron::de::from_reader(file).map_err(|error| AssetError::Atlas {
+        path: path.to_string(),
+        error: error.to_string(),
+    })
+}
+
+impl fmt::Debug for AssetCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AssetCache")
+            .field("root", &self.root)
+            .field("images", &self.images.keys().collect::<Vec<_>>())
+            .field("atlases", &self.atlases.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
@@ -0,0 +1,328 @@
+use crate::resources::*;
+use ggez::event::{Axis, Button, KeyCode, KeyMods, MouseButton};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Everything a binding can trigger.
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug)]
+pub enum Action {
+    Player(PlayerAction),
+    ToggleColliders,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Modifier {
+    Shift,
+    Ctrl,
+    Alt,
+    Logo,
+}
+
+impl Modifier {
+    fn key_mods(self) -> KeyMods {
+        match self {
+            Modifier::Shift => KeyMods::SHIFT,
+            Modifier::Ctrl => KeyMods::CTRL,
+            Modifier::Alt => KeyMods::ALT,
+            Modifier::Logo => KeyMods::LOGO,
+        }
+    }
+
+    fn from_key_mods(key_mods: KeyMods) -> Vec<Modifier> {
+        [
+            Modifier::Shift,
+            Modifier::Ctrl,
+            Modifier::Alt,
+            Modifier::Logo,
+        ]
+        .iter()
+        .cloned()
+        .filter(|modifier| key_mods.contains(modifier.key_mods()))
+        .collect()
+    }
+}
+
+/// Which way a gamepad axis has to be deflected for a binding to trigger.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum AxisDirection {
+    Positive,
+    Negative,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug)]
+pub enum Binding {
+    /// Active while the key is held, regardless of modifiers.
+    Key(KeyCode),
+    /// Active while the key is held together with all of the modifiers.
+    Chord(Vec<Modifier>, KeyCode),
+    Mouse(MouseButton),
+    GamepadButton(Button),
+    /// Gives the deflection of the axis in the direction as an analog value, once it's deflected
+    /// past `AXIS_ACTIVATION_THRESHOLD`.
+    GamepadAxis(Axis, AxisDirection),
+}
+
+impl Binding {
+    /// How strongly the binding is held from 0 to 1. Only gamepad axes give values in between.
+    pub fn value(&self, input_context: &InputContext) -> f64 {
+        self.value_at(input_context, false)
+    }
+
+    pub fn is_active(&self, input_context: &InputContext) -> bool {
+        self.value(input_context) > 0.0
+    }
+
+    /// Whether the binding was active on the previous tick, for bindings that trigger once per press.
+    pub fn was_active(&self, input_context: &InputContext) -> bool {
+        self.value_at(input_context, true) > 0.0
+    }
+
+    fn value_at(&self, input_context: &InputContext, last_tick: bool) -> f64 {
+        let (pressed_keys, mouse_buttons, gamepad_buttons, gamepad_axes) = if last_tick {
+            (
+                &input_context.last_pressed_keys,
+                &input_context.last_mouse_buttons,
+                &input_context.last_gamepad_buttons,
+                &input_context.last_gamepad_axes,
+            )
+        } else {
+            (
+                &input_context.pressed_keys,
+                &input_context.mouse_buttons,
+                &input_context.gamepad_buttons,
+                &input_context.gamepad_axes,
+            )
+        };
+        let held = match self {
+            Binding::Key(key) => pressed_keys.contains(key),
+            Binding::Chord(modifiers, key) => {
+                pressed_keys.contains(key)
+                    && modifiers
+                        .iter()
+                        .all(|modifier| input_context.active_mods.contains(modifier.key_mods()))
+            }
+            Binding::Mouse(button) => mouse_buttons.contains(button),
+            Binding::GamepadButton(button) => gamepad_buttons.contains(button),
+            Binding::GamepadAxis(axis, direction) => {
+                let value = f64::from(*gamepad_axes.get(axis).unwrap_or(&0.0));
+                let value = match direction {
+                    AxisDirection::Positive => value,
+                    AxisDirection::Negative => -value,
+                };
+                return if value >= AXIS_ACTIVATION_THRESHOLD {
+                    value
+                } else {
+                    0.0
+                };
+            }
+        };
+        if held {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KeyBindings {
+    pub bindings: BTreeMap<Action, Vec<Binding>>,
+    /// Set to rebind an action to the next key, chord or mouse button pressed.
+    #[serde(skip)]
+    pub pending_rebind: Option<Action>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let bindings = [
+            (
+                Action::Player(PlayerAction::MoveNorth),
+                vec![
+                    Binding::Key(KeyCode::Up),
+                    Binding::Key(KeyCode::W),
+                    Binding::GamepadButton(Button::DPadUp),
+                    Binding::GamepadAxis(Axis::LeftStickY, AxisDirection::Positive),
+                ],
+            ),
+            (
+                Action::Player(PlayerAction::MoveSouth),
+                vec![
+                    Binding::Key(KeyCode::Down),
+                    Binding::Key(KeyCode::S),
+                    Binding::GamepadButton(Button::DPadDown),
+                    Binding::GamepadAxis(Axis::LeftStickY, AxisDirection::Negative),
+                ],
+            ),
+            (
+                Action::Player(PlayerAction::MoveWest),
+                vec![
+                    Binding::Key(KeyCode::Left),
+                    Binding::Key(KeyCode::A),
+                    Binding::GamepadButton(Button::DPadLeft),
+                    Binding::GamepadAxis(Axis::LeftStickX, AxisDirection::Negative),
+                ],
+            ),
+            (
+                Action::Player(PlayerAction::MoveEast),
+                vec![
+                    Binding::Key(KeyCode::Right),
+                    Binding::Key(KeyCode::D),
+                    Binding::GamepadButton(Button::DPadRight),
+                    Binding::GamepadAxis(Axis::LeftStickX, AxisDirection::Positive),
+                ],
+            ),
+            (Action::ToggleColliders, vec![Binding::Key(KeyCode::F1)]),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        KeyBindings {
+            bindings,
+            pending_rebind: None,
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<KeyBindings> {
+        let file = fs::File::open(path)?;
+        ron::de::from_reader(file)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let serialized = ron::ser::to_string_pretty(self, Default::default())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        fs::write(path, serialized)
+    }
+
+    pub fn bindings_for(&self, action: &Action) -> &[Binding] {
+        self.bindings
+            .get(action)
+            .map(|bindings| bindings.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// The strongest value of any of the action's bindings.
+    pub fn value(&self, action: &Action, input_context: &InputContext) -> f64 {
+        self.bindings_for(action)
+            .iter()
+            .map(|binding| binding.value(input_context))
+            .fold(0.0, f64::max)
+    }
+
+    pub fn is_active(&self, action: &Action, input_context: &InputContext) -> bool {
+        self.value(action, input_context) > 0.0
+    }
+
+    /// Whether the action became active this tick.
+    pub fn is_just_active(&self, action: &Action, input_context: &InputContext) -> bool {
+        self.bindings_for(action)
+            .iter()
+            .any(|binding| binding.is_active(input_context) && !binding.was_active(input_context))
+    }
+
+    pub fn bind(&mut self, action: Action, binding: Binding) {
+        let bindings = self.bindings.entry(action).or_insert_with(Vec::new);
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    pub fn unbind(&mut self, action: &Action, binding: &Binding) {
+        if let Some(bindings) = self.bindings.get_mut(action) {
+            bindings.retain(|existing| existing != binding);
+        }
+    }
+
+    /// Replaces all bindings of the action with the next input pressed.
+    pub fn rebind_next(&mut self, action: Action) {
+        self.pending_rebind = Some(action);
+    }
+
+    /// Completes a pending rebind if a key, mouse button, gamepad button or stick was pressed this
+    /// tick. Modifier keys on their own don't complete it, they become part of a chord with the
+    /// next key instead.
+    pub fn capture_rebind(&mut self, input_context: &InputContext) {
+        let action = match &self.pending_rebind {
+            Some(action) => action.clone(),
+            None => return,
+        };
+        let key = input_context
+            .pressed_keys
+            .difference(&input_context.last_pressed_keys)
+            .find(|key| !is_modifier_key(**key));
+        let binding = match key {
+            Some(key) => {
+                let modifiers = Modifier::from_key_mods(input_context.active_mods);
+                if modifiers.is_empty() {
+                    Binding::Key(*key)
+                } else {
+                    Binding::Chord(modifiers, *key)
+                }
+            }
+            None => match captured_button(input_context) {
+                Some(binding) => binding,
+                None => return,
+            },
+        };
+        self.bindings.insert(action, vec![binding]);
+        self.pending_rebind = None;
+    }
+}
+
+/// How far a stick has to be pushed for its bindings to become active, so a stick resting slightly
+/// off center doesn't hold actions.
+pub const AXIS_ACTIVATION_THRESHOLD: f64 = 0.2;
+
+/// How far a stick has to be pushed to be captured by a rebind.
+const REBIND_AXIS_THRESHOLD: f32 = 0.5;
+
+fn captured_button(input_context: &InputContext) -> Option<Binding> {
+    if let Some(button) = input_context
+        .mouse_buttons
+        .difference(&input_context.last_mouse_buttons)
+        .next()
+    {
+        return Some(Binding::Mouse(*button));
+    }
+    if let Some(button) = input_context
+        .gamepad_buttons
+        .difference(&input_context.last_gamepad_buttons)
+        .next()
+    {
+        return Some(Binding::GamepadButton(*button));
+    }
+    input_context
+        .gamepad_axes
+        .iter()
+        .find(|(axis, value)| {
+            let last_value = *input_context.last_gamepad_axes.get(axis).unwrap_or(&0.0);
+            value.abs() >= REBIND_AXIS_THRESHOLD && last_value.abs() < REBIND_AXIS_THRESHOLD
+        })
+        .map(|(axis, value)| {
+            let direction = if *value > 0.0 {
+                AxisDirection::Positive
+            } else {
+                AxisDirection::Negative
+            };
+            Binding::GamepadAxis(*axis, direction)
+        })
+}
+
+fn is_modifier_key(key: KeyCode) -> bool {
+    match key {
+        KeyCode::LShift
+        | KeyCode::RShift
+        | KeyCode::LControl
+        | KeyCode::RControl
+        | KeyCode::LAlt
+        | KeyCode::RAlt
+        | KeyCode::LWin
+        | KeyCode::RWin => true,
+        _ => false,
+    }
+}
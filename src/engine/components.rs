@@ -1,25 +1,295 @@
+use crate::animation::*;
 use ggez::graphics;
+use nalgebra::Isometry2;
+use nalgebra::Point2;
+use nalgebra::Vector2;
+use nphysics2d::force_generator::DefaultForceGeneratorHandle;
+use nphysics2d::joint::DefaultJointConstraintHandle;
 use nphysics2d::object::DefaultBodyHandle;
 use nphysics2d::object::DefaultColliderHandle;
+use serde::{Deserialize, Serialize};
 use specs::DenseVecStorage;
-use specs::{Component, VecStorage};
+use specs::{Component, Entity, VecStorage};
+use std::collections::HashMap;
 
 #[derive(Component, Debug)]
 #[storage(VecStorage)]
 pub struct TransformComponent(pub DefaultBodyHandle);
 
+/// The body position before the latest physics step, used to interpolate drawing between ticks.
+#[derive(Component, Debug)]
+#[storage(VecStorage)]
+pub struct PreviousTransform(pub Isometry2<f64>);
+
 #[derive(Component, Debug)]
 #[storage(VecStorage)]
 pub struct Sprite {
     pub image: graphics::Image,
+    /// The file the image was loaded from, for saving scenes.
+    pub path: String,
+    /// The cache key of the texture the image holds: the path, or `PLACEHOLDER_PATH` if the image
+    /// or frame failed to load.
+    pub texture: String,
+    /// The frame of the image's atlas the sprite shows, or the whole image if none.
+    pub frame: Option<String>,
+    /// The part of the image to draw, as a fraction of its size.
+    pub src: graphics::Rect,
 }
 
-#[derive(Component, Debug, Default)]
+#[derive(Component, Serialize, Deserialize, Clone, Debug, Default)]
 #[storage(DenseVecStorage)]
 pub struct Player {
     pub movement_speed: f64,
 }
 
-#[derive(Component)]
+/// Every collider attached to the entity's body.
+#[derive(Component, Debug)]
 #[storage(VecStorage)]
-pub struct ColliderComponent(pub DefaultColliderHandle);
+pub struct ColliderComponent(pub Vec<DefaultColliderHandle>);
+
+/// Scales the world's gravity for the entity's body. Only present for bodies whose gravity isn't
+/// the world's as is.
+#[derive(Component, Debug)]
+#[storage(DenseVecStorage)]
+pub struct GravityScale(pub f64);
+
+/// The mass the entity's body was created with. The body's own mass also counts the mass of its
+/// colliders, so this is what scenes save to create the same body again.
+#[derive(Component, Debug)]
+#[storage(DenseVecStorage)]
+pub struct BodyMass(pub f64);
+
+/// Moves the entity's body by sweeping its solid colliders along the desired motion each tick,
+/// stopping at the first hit and sliding along the surface for the rest of the motion. Meant for
+/// kinematic bodies, which aren't moved by the physics step themselves.
+#[derive(Component, Clone, Debug)]
+#[storage(DenseVecStorage)]
+pub struct CharacterController {
+    /// The velocity to move at, set by gameplay systems such as `ActionSystem`.
+    pub desired_velocity: Vector2<f64>,
+    /// Distance kept to whatever the colliders hit, so the next move doesn't start in contact.
+    pub skin_width: f64,
+    /// How many times a move can be deflected along surfaces in one tick.
+    pub max_slides: u32,
+    /// Whether the last move hit something.
+    pub collided: bool,
+}
+
+impl Default for CharacterController {
+    fn default() -> Self {
+        CharacterController {
+            desired_velocity: Vector2::zeros(),
+            skin_width: 0.1,
+            max_slides: 4,
+            collided: false,
+        }
+    }
+}
+
+/// Connects the bodies of two entities. Lives on an entity of its own, which is deleted along with
+/// either of the connected entities.
+#[derive(Component, Debug)]
+#[storage(DenseVecStorage)]
+pub struct JointComponent {
+    pub entity1: Entity,
+    pub entity2: Entity,
+    pub joint: Joint,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Joint {
+    /// A joint constraint solved by nphysics.
+    Constraint(DefaultJointConstraintHandle),
+    /// Keeps the anchors, local to each body, at most `length` apart. Solved by the engine since
+    /// nphysics has no rope joints.
+    Rope {
+        anchor1: Point2<f64>,
+        anchor2: Point2<f64>,
+        length: f64,
+    },
+}
+
+/// Force generators the entity's body is the source of, or one end of in the case of springs.
+#[derive(Component, Debug)]
+#[storage(DenseVecStorage)]
+pub struct ForceGeneratorComponent(pub Vec<DefaultForceGeneratorHandle>);
+
+/// Named groups of sprites, drawn back to front in declaration order.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RenderLayer {
+    Background,
+    World,
+    Foreground,
+    /// Drawn in screen space, positioned in pixels and unaffected by the camera.
+    Ui,
+}
+
+impl RenderLayer {
+    /// Whether sprites lower on the screen are drawn over higher ones, so characters in top-down
+    /// scenes overlap what's behind them.
+    pub fn y_sorted(self) -> bool {
+        self == RenderLayer::World
+    }
+}
+
+impl Default for RenderLayer {
+    fn default() -> Self {
+        RenderLayer::World
+    }
+}
+
+/// Where the entity's sprite is drawn relative to other sprites. Sprites without one are drawn in
+/// the world layer at z 0.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[storage(DenseVecStorage)]
+pub struct RenderOrder {
+    #[serde(default)]
+    pub layer: RenderLayer,
+    /// Sprites with a higher z are drawn over those with a lower z in the same layer, regardless of
+    /// y-sorting.
+    #[serde(default)]
+    pub z: i32,
+}
+
+/// Plays clips of atlas frames on the entity's sprite, advanced by the animation system every tick.
+#[derive(Component, Clone, Debug)]
+#[storage(DenseVecStorage)]
+pub struct Animation {
+    pub clips: HashMap<String, AnimationClip>,
+    clip: String,
+    frame: usize,
+    /// Time the current frame has been shown for.
+    elapsed: f64,
+    finished: bool,
+}
+
+impl Animation {
+    pub fn new(clips: HashMap<String, AnimationClip>, initial_clip: &str) -> Animation {
+        Animation {
+            clips,
+            clip: initial_clip.to_string(),
+            frame: 0,
+            elapsed: 0.0,
+            finished: false,
+        }
+    }
+
+    /// Switches to the clip, from its first frame. Keeps playing if the clip is already playing, so
+    /// it can be called every tick. Returns false if there is no clip with the name.
+    pub fn play(&mut self, clip: &str) -> bool {
+        if !self.clips.contains_key(clip) {
+            return false;
+        }
+        if self.clip != clip {
+            self.clip = clip.to_string();
+            self.restart();
+        }
+        true
+    }
+
+    /// Plays the current clip again from its first frame.
+    pub fn restart(&mut self) {
+        self.frame = 0;
+        self.elapsed = 0.0;
+        self.finished = false;
+    }
+
+    pub fn current_clip(&self) -> &str {
+        &self.clip
+    }
+
+    /// Name of the atlas frame currently shown.
+    pub fn current_frame(&self) -> Option<&str> {
+        self.clips
+            .get(&self.clip)
+            .and_then(|clip| clip.frames.get(self.frame))
+            .map(|frame| frame.frame.as_str())
+    }
+
+    /// Whether a one-shot clip is done and holding its last frame.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Whether a one-shot clip is still on its way to its last frame.
+    pub fn is_playing_once(&self) -> bool {
+        let once = self
+            .clips
+            .get(&self.clip)
+            .map(|clip| clip.mode == PlaybackMode::Once)
+            .unwrap_or(false);
+        once && !self.finished
+    }
+
+    /// Moves the clip forward by the delta in seconds, returning whether it reached its end.
+    pub fn advance(&mut self, delta: f64) -> bool {
+        let clip = match self.clips.get(&self.clip) {
+            Some(clip) => clip,
+            None => return false,
+        };
+        let duration = clip.duration();
+        if self.finished || duration <= 0.0 {
+            return false;
+        }
+        if clip.frames.get(self.frame).is_none() {
+            // The clip was shortened since the frame was reached
+            self.frame = 0;
+            self.elapsed = 0.0;
+        }
+        self.elapsed += delta;
+        let mut reached_end = false;
+        if clip.mode == PlaybackMode::Loop && self.elapsed >= duration {
+            // Skip whole loops at once so a long hitch doesn't step through them frame by frame
+            self.elapsed %= duration;
+            reached_end = true;
+        }
+        // Frames with a negative duration are skipped like those with none, as adding it to the
+        // elapsed time would never let it run out
+        while self.elapsed >= clip.frames[self.frame].duration.max(0.0) {
+            self.elapsed -= clip.frames[self.frame].duration.max(0.0);
+            if self.frame + 1 < clip.frames.len() {
+                self.frame += 1;
+            } else if clip.mode == PlaybackMode::Loop {
+                self.frame = 0;
+                reached_end = true;
+            } else {
+                self.elapsed = 0.0;
+                self.finished = true;
+                return true;
+            }
+        }
+        reached_end
+    }
+}
+
+/// Clips `ActionSystem` plays on a player's animation as it moves, by the axis moved along most.
+/// Directions without a clip leave the animation as is.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, Default)]
+#[storage(DenseVecStorage)]
+pub struct MovementClips {
+    pub idle: Option<String>,
+    pub north: Option<String>,
+    pub south: Option<String>,
+    pub west: Option<String>,
+    pub east: Option<String>,
+}
+
+impl MovementClips {
+    /// The clip for moving in the direction, with y pointing south like screen coordinates.
+    pub fn clip_for(&self, movement: &Vector2<f64>) -> Option<&str> {
+        let clip = if *movement == Vector2::zeros() {
+            &self.idle
+        } else if movement.x.abs() > movement.y.abs() {
+            if movement.x > 0.0 {
+                &self.east
+            } else {
+                &self.west
+            }
+        } else if movement.y > 0.0 {
+            &self.south
+        } else {
+            &self.north
+        };
+        clip.as_ref().map(|clip| clip.as_str())
+    }
+}
@@ -0,0 +1,86 @@
+use crate::assets::AssetError;
+use ggez::GameError;
+use nphysics2d::object::DefaultBodyHandle;
+use specs::error::WrongGeneration;
+use specs::Entity;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum EngineError {
+    /// Creating the window or its graphics context failed, or the event loop did.
+    Context(GameError),
+    /// The game state has no window to run or draw with, use `step` instead.
+    Headless,
+    Asset(AssetError),
+    /// Reading or writing a recording, key bindings or scene failed.
+    Io(io::Error),
+    /// Watching the assets folder for changes failed.
+    Watch(notify::Error),
+    /// `save_recording` was called without `start_recording`.
+    NotRecording,
+    /// The entity has no body to attach physics objects to.
+    NoBody(Entity),
+    /// The handle refers to a body that has since been removed.
+    StaleBody(DefaultBodyHandle),
+    /// The entity was deleted before components could be added to it.
+    DeadEntity(Entity),
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::Context(error) => write!(f, "ggez failed: {}", error),
+            EngineError::Headless => {
+                write!(f, "Cannot run a headless game state, use step instead")
+            }
+            EngineError::Asset(error) => write!(f, "{}", error),
+            EngineError::Io(error) => write!(f, "{}", error),
+            EngineError::Watch(error) => write!(f, "Failed watching assets: {}", error),
+            EngineError::NotRecording => {
+                write!(f, "Attempted to save recording while not recording")
+            }
+            EngineError::NoBody(entity) => write!(f, "Entity {:?} has no body", entity),
+            EngineError::StaleBody(handle) => {
+                write!(f, "Body no longer existing for handle {:?}", handle)
+            }
+            EngineError::DeadEntity(entity) => write!(f, "Entity {:?} is no longer alive", entity),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EngineError::Context(error) => Some(error),
+            EngineError::Asset(error) => Some(error),
+            EngineError::Io(error) => Some(error),
+            EngineError::Watch(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<AssetError> for EngineError {
+    fn from(error: AssetError) -> EngineError {
+        EngineError::Asset(error)
+    }
+}
+
+impl From<io::Error> for EngineError {
+    fn from(error: io::Error) -> EngineError {
+        EngineError::Io(error)
+    }
+}
+
+impl From<notify::Error> for EngineError {
+    fn from(error: notify::Error) -> EngineError {
+        EngineError::Watch(error)
+    }
+}
+
+impl From<WrongGeneration> for EngineError {
+    fn from(error: WrongGeneration) -> EngineError {
+        EngineError::DeadEntity(error.entity)
+    }
+}
@@ -0,0 +1,171 @@
+use crate::assets::*;
+use crate::bindings::KeyBindings;
+use crate::components::*;
+use crate::scene::{reapply_scene, Scene};
+use ggez::Context;
+use notify::{watcher, DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use specs::*;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
+
+const IMAGE_EXTENSIONS: [&str; 4] = ["png", "jpg", "jpeg", "bmp"];
+
+/// Config and scene files loaded from disk, by canonical path, so they can be applied again when
+/// they change.
+#[derive(Default, Debug)]
+pub struct LoadedFiles {
+    pub bindings: Option<PathBuf>,
+    pub scenes: HashMap<PathBuf, Vec<Entity>>,
+}
+
+/// Watches the assets folder for changed files.
+pub struct AssetWatcher {
+    root: PathBuf,
+    // Kept alive for as long as events should be received
+    _watcher: RecommendedWatcher,
+    events: Receiver<DebouncedEvent>,
+}
+
+impl AssetWatcher {
+    pub fn new(root: &Path) -> notify::Result<AssetWatcher> {
+        let (sender, events) = channel();
+        let mut watcher = watcher(sender, Duration::from_millis(200))?;
+        watcher.watch(root, RecursiveMode::Recursive)?;
+        Ok(AssetWatcher {
+            root: fs::canonicalize(root)?,
+            _watcher: watcher,
+            events,
+        })
+    }
+
+    /// Files created or written to since the last call, each listed once.
+    pub fn changed_files(&self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for event in self.events.try_iter() {
+            let path = match event {
+                DebouncedEvent::Create(path)
+                | DebouncedEvent::Write(path)
+                | DebouncedEvent::Rename(_, path) => path,
+                DebouncedEvent::Error(e, path) => {
+                    log::warn!("Error watching assets at {:?}: {}", path, e);
+                    continue;
+                }
+                _ => continue,
+            };
+            if !changed.contains(&path) {
+                changed.push(path);
+            }
+        }
+        changed
+    }
+}
+
+/// Applies every changed file the watcher has seen: images and atlases are swapped into the
+/// sprites using them, and key bindings and scenes are loaded again.
+pub(crate) fn reload_changed(world: &mut World, context: &mut Context, watcher: &AssetWatcher) {
+    for path in watcher.changed_files() {
+        let path = fs::canonicalize(&path).unwrap_or(path);
+        let is_image = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| IMAGE_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+            .unwrap_or(false);
+        if is_image {
+            if let Ok(relative) = path.strip_prefix(&watcher.root) {
+                reload_image(world, context, &normalize_path(&relative.to_string_lossy()));
+            }
+            continue;
+        }
+
+        if let Ok(relative) = path.strip_prefix(&watcher.root) {
+            let reloaded = world
+                .write_resource::<AssetCache>()
+                .reload_atlas(context, &normalize_path(&relative.to_string_lossy()));
+            match reloaded {
+                Some(Ok(image_path)) => {
+                    refresh_frames(world, context, &image_path);
+                    continue;
+                }
+                Some(Err(e)) => {
+                    log::warn!("Failed reloading atlas: {}", e);
+                    continue;
+                }
+                None => {}
+            }
+        }
+
+        let is_bindings = world.read_resource::<LoadedFiles>().bindings.as_ref() == Some(&path);
+        if is_bindings {
+            match KeyBindings::load(&path) {
+                Ok(bindings) => *world.write_resource::<KeyBindings>() = bindings,
+                Err(e) => log::warn!("Failed reloading key bindings {:?}: {}", path, e),
+            }
+            continue;
+        }
+
+        let scene_entities = world
+            .read_resource::<LoadedFiles>()
+            .scenes
+            .get(&path)
+            .cloned();
+        if let Some(entities) = scene_entities {
+            match Scene::load(&path) {
+                Ok(scene) => {
+                    let entities = reapply_scene(world, Some(&mut *context), &scene, &entities);
+                    world
+                        .write_resource::<LoadedFiles>()
+                        .scenes
+                        .insert(path, entities);
+                }
+                Err(e) => log::warn!("Failed reloading scene {:?}: {}", path, e),
+            }
+        }
+    }
+}
+
+fn reload_image(world: &World, context: &mut Context, path: &str) {
+    // Sprites showing the placeholder have nothing cached for their path, but still want the image
+    let used = (&world.read_storage::<Sprite>())
+        .join()
+        .any(|sprite| normalize_path(&sprite.path) == path);
+    if !used && !world.read_resource::<AssetCache>().is_loaded(path) {
+        return;
+    }
+    let image = match world.write_resource::<AssetCache>().reload(context, path) {
+        Ok(image) => image,
+        Err(e) => {
+            log::warn!("Failed reloading image: {}", e);
+            return;
+        }
+    };
+    for sprite in (&mut world.write_storage::<Sprite>()).join() {
+        if normalize_path(&sprite.path) == path {
+            // Keep the filter each sprite set on its own copy of the image
+            let filter = sprite.image.filter();
+            sprite.image = image.clone();
+            sprite.image.set_filter(filter);
+            sprite.texture = path.to_string();
+        }
+    }
+    // The image may have been resized, moving its frames
+    refresh_frames(world, context, path);
+}
+
+/// Looks up the frames of the sprites using the image again.
+fn refresh_frames(world: &World, context: &mut Context, path: &str) {
+    let mut asset_cache = world.write_resource::<AssetCache>();
+    for sprite in (&mut world.write_storage::<Sprite>()).join() {
+        if normalize_path(&sprite.path) != path {
+            continue;
+        }
+        if let Some(frame) = &sprite.frame {
+            match asset_cache.frame(context, path, frame) {
+                Ok(src) => sprite.src = src,
+                Err(e) => log::warn!("Failed reloading frame: {}", e),
+            }
+        }
+    }
+}
@@ -1,67 +1,111 @@
 use crate::resources::*;
+use animation::*;
+use assets::*;
+use bindings::*;
 use components::*;
+use error::*;
 use ggez::conf::WindowMode;
 use ggez::conf::WindowSetup;
 use ggez::event;
 use ggez::event::EventHandler;
 use ggez::event::EventsLoop;
+use ggez::event::{Axis, Button, GamepadId};
 use ggez::graphics;
 pub use ggez::graphics::FilterMode;
 use ggez::input::keyboard::*;
+use ggez::input::mouse;
 use ggez::timer;
 use ggez::Context;
 use ggez::ContextBuilder;
 use ggez::GameResult;
-use nalgebra::Vector2;
+use hot_reload::*;
+use nalgebra::{Isometry2, Point2, Vector2};
 use ncollide2d::shape::ShapeHandle;
+use nphysics2d::force_generator::DefaultForceGeneratorHandle;
 use nphysics2d::force_generator::DefaultForceGeneratorSet;
 use nphysics2d::joint::DefaultJointConstraintSet;
-use nphysics2d::material::BasicMaterial;
-use nphysics2d::material::MaterialHandle;
-use nphysics2d::object::BodyPartHandle;
-use nphysics2d::object::ColliderDesc;
+use nphysics2d::math::{Force, ForceType};
+use nphysics2d::object::Body;
+use nphysics2d::object::DefaultBodyHandle;
 use nphysics2d::object::DefaultBodySet;
 use nphysics2d::object::DefaultColliderHandle;
 use nphysics2d::object::DefaultColliderSet;
-use nphysics2d::object::RigidBodyDesc;
 use nphysics2d::world::DefaultGeometricalWorld;
 use nphysics2d::world::DefaultMechanicalWorld;
+use physics::body::*;
+use physics::collider::*;
+use physics::events::*;
+use physics::forces::*;
+use physics::joint::*;
 use physics::resources::*;
+use replay::*;
+use specs::shrev::EventChannel;
 pub use specs::world::Builder;
 use specs::*;
 pub use specs::{Entity, EntityBuilder};
 use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 pub use uuid::Uuid;
 use systems::input_system::InputSystem;
 use systems::action_system::ActionSystem;
+use systems::animation_system::AnimationSystem;
+use systems::camera_system::CameraSystem;
+use systems::character_controller_system::CharacterControllerSystem;
+use systems::collision_event_system::CollisionEventSystem;
 use systems::draw_system::DrawSystem;
+use systems::penetration_system::PenetrationSystem;
+use systems::rope_system::RopeSystem;
 
+pub mod animation;
+pub mod assets;
+pub mod bindings;
 pub mod components;
+pub mod error;
+pub mod hot_reload;
 pub mod physics;
+pub mod replay;
 pub mod resources;
+pub mod scene;
 pub mod systems;
 
 pub struct GameState<'a, 'b> {
     ecs: ECS<'a, 'b>,
-    context: Context,
-    event_loop: EventsLoop,
+    context: Option<Context>,
+    event_loop: Option<EventsLoop>,
 }
 
 pub struct ECS<'a, 'b> {
     world: World,
     dispatcher: Dispatcher<'a, 'b>,
+    asset_watcher: Option<AssetWatcher>,
 }
 
 fn register_components(world: &mut World) {
     world.register::<TransformComponent>();
+    world.register::<PreviousTransform>();
     world.register::<Sprite>();
     world.register::<Player>();
     world.register::<ColliderComponent>();
+    world.register::<GravityScale>();
+    world.register::<BodyMass>();
+    world.register::<CharacterController>();
+    world.register::<JointComponent>();
+    world.register::<ForceGeneratorComponent>();
+    world.register::<RenderOrder>();
+    world.register::<Animation>();
+    world.register::<MovementClips>();
 }
 
 fn insert_resources(world: &mut World) {
     world.insert(DeltaTime(0.0));
+    world.insert(AssetCache::new(find_assets_folder()));
+    world.insert(LoadedFiles::default());
+    world.insert(FixedTimestep::default());
+    world.insert(InputContext::default());
+    world.insert(Camera::default());
     world.insert(ActionContext::new());
+    world.insert(InputReplay::default());
+    world.insert(KeyBindings::default());
     world.insert(GameOptions {
         draw_colliders: false,
     });
@@ -77,23 +121,61 @@ fn insert_resources(world: &mut World) {
     world.insert(MyBodySet {
         0: DefaultBodySet::new(),
     });
+    world.insert(EntityBodies::default());
     world.insert(MyColliderSet {
         0: DefaultColliderSet::new(),
     });
     world.insert(MyJointConstraintSet {
         0: DefaultJointConstraintSet::new(),
     });
+    world.insert(EntityJoints::default());
     world.insert(MyForceGeneratorSet {
         0: DefaultForceGeneratorSet::new(),
     });
+    world.insert(EventChannel::<CollisionEvent>::new());
+    world.insert(EventChannel::<AnimationEvent>::new());
 }
 
-pub fn new_game_state(title: &str, size: (f32, f32)) -> GameState {
+fn build_dispatcher<'a, 'b>() -> Dispatcher<'a, 'b> {
+    DispatcherBuilder::new()
+        .with(InputSystem, "input_system", &[])
+        .with(ActionSystem, "action_system", &["input_system"])
+        .with(CollisionEventSystem, "collision_event_system", &[])
+        .with(AnimationSystem, "animation_system", &["action_system"])
+        .with(
+            CharacterControllerSystem,
+            "character_controller_system",
+            &["action_system"],
+        )
+        .with(
+            PenetrationSystem,
+            "penetration_system",
+            &["character_controller_system"],
+        )
+        .with(RopeSystem, "rope_system", &["penetration_system"])
+        .build()
+}
+
+fn build_ecs<'a, 'b>() -> ECS<'a, 'b> {
     let mut world = World::new();
     register_components(&mut world);
     insert_resources(&mut world);
 
-    let (context, event_loop) = ContextBuilder::new(title, "TEST")
+    ECS {
+        world,
+        dispatcher: build_dispatcher(),
+        asset_watcher: None,
+    }
+}
+
+pub fn new_game_state(title: &str, size: (f32, f32)) -> Result<GameState, EngineError> {
+    let ecs = build_ecs();
+    let mut context_builder = ContextBuilder::new(title, "TEST");
+    if let Some(root) = &ecs.world.read_resource::<AssetCache>().root {
+        context_builder = context_builder.add_resource_path(root.clone());
+    }
+    *ecs.world.write_resource::<Camera>() = Camera::new(Vector2::new(size.0 as f64, size.1 as f64));
+    let (context, event_loop) = context_builder
         .window_mode(WindowMode {
             width: size.0,
             height: size.1,
@@ -105,39 +187,186 @@ pub fn new_game_state(title: &str, size: (f32, f32)) -> GameState {
             ..Default::default()
         })
         .build()
-        .expect("Could not create ggez context!");
+        .map_err(EngineError::Context)?;
 
-    let dispatcher = DispatcherBuilder::new()
-        .with(InputSystem, "input_system", &[])
-        .with(ActionSystem, "action_system", &["input_system"])
-        .build();
+    Ok(GameState {
+        ecs,
+        context: Some(context),
+        event_loop: Some(event_loop),
+    })
+}
+
+/// Creates a game state without a window, for running the simulation where there is no display,
+/// such as in tests. Advance it with `step` instead of `run`.
+pub fn new_headless_game_state<'a, 'b>() -> GameState<'a, 'b> {
     GameState {
-        ecs: ECS { world, dispatcher },
-        context,
-        event_loop,
+        ecs: build_ecs(),
+        context: None,
+        event_loop: None,
     }
 }
 
-impl EventHandler for ECS<'_, '_> {
-    fn update(&mut self, context: &mut Context) -> GameResult<()> {
+/// Advances the simulation by the given number of fixed ticks.
+pub fn step(game_state: &mut GameState, ticks: u32) {
+    if let Some(context) = game_state.context.as_mut() {
+        load_animation_atlases(&game_state.ecs.world, context);
+    }
+    let delta = game_state.ecs.world.read_resource::<FixedTimestep>().step;
+    for _ in 0..ticks {
+        game_state.ecs.tick(delta);
+    }
+}
+
+pub fn world<'s>(game_state: &'s GameState) -> &'s World {
+    &game_state.ecs.world
+}
+
+pub fn world_mut<'s>(game_state: &'s mut GameState) -> &'s mut World {
+    &mut game_state.ecs.world
+}
+
+/// Makes the camera follow the entity, or stop following anything. The camera moves while drawing,
+/// following the position the entity is drawn at.
+pub fn set_camera_target(game_state: &mut GameState, target: Option<Entity>) {
+    game_state.ecs.world.write_resource::<Camera>().target = target;
+}
+
+/// Converts a point on the screen in pixels, such as the mouse position, to the world.
+pub fn screen_to_world(game_state: &GameState, point: Point2<f64>) -> Point2<f64> {
+    game_state
+        .ecs
+        .world
+        .read_resource::<Camera>()
+        .screen_to_world(&point)
+}
+
+pub fn world_to_screen(game_state: &GameState, point: Point2<f64>) -> Point2<f64> {
+    game_state
+        .ecs
+        .world
+        .read_resource::<Camera>()
+        .world_to_screen(&point)
+}
+
+/// Starts recording the input of every tick until `save_recording` is called.
+pub fn start_recording(game_state: &mut GameState) {
+    let step = game_state.ecs.world.read_resource::<FixedTimestep>().step;
+    *game_state.ecs.world.write_resource::<InputReplay>() =
+        InputReplay::Recording(InputRecording {
+            step,
+            frames: Vec::new(),
+        });
+}
+
+/// Stops recording and writes the recorded ticks to the given file.
+pub fn save_recording<P: AsRef<Path>>(
+    game_state: &mut GameState,
+    path: P,
+) -> Result<(), EngineError> {
+    let mut replay = game_state.ecs.world.write_resource::<InputReplay>();
+    match std::mem::replace(&mut *replay, InputReplay::Live) {
+        InputReplay::Recording(recording) => Ok(recording.save(path)?),
+        other => {
+            *replay = other;
+            Err(EngineError::NotRecording)
+        }
+    }
+}
+
+/// Replaces live input with the ticks of a recording, switching back to live input when it ends.
+/// The fixed timestep is set to the one the recording was made with.
+pub fn play_recording<P: AsRef<Path>>(
+    game_state: &mut GameState,
+    path: P,
+) -> Result<(), EngineError> {
+    let recording = InputRecording::load(path)?;
+    game_state.ecs.world.write_resource::<FixedTimestep>().step = recording.step;
+    *game_state.ecs.world.write_resource::<InputReplay>() = InputReplay::Playback {
+        recording,
+        next_frame: 0,
+    };
+    Ok(())
+}
+
+/// Replaces the key bindings with the ones in the given file.
+pub fn load_bindings<P: AsRef<Path>>(
+    game_state: &mut GameState,
+    path: P,
+) -> Result<(), EngineError> {
+    let bindings = KeyBindings::load(&path)?;
+    *game_state.ecs.world.write_resource::<KeyBindings>() = bindings;
+    game_state
+        .ecs
+        .world
+        .write_resource::<LoadedFiles>()
+        .bindings = Some(std::fs::canonicalize(path)?);
+    Ok(())
+}
+
+/// The assets folder images are loaded from, to find other files shipped with the game in.
+pub fn assets_folder(game_state: &GameState) -> Option<PathBuf> {
+    game_state
+        .ecs
+        .world
+        .read_resource::<AssetCache>()
+        .root
+        .clone()
+}
+
+/// Starts watching the assets folder, swapping in changed images and applying changed key bindings
+/// and scenes while the game runs.
+pub fn watch_assets(game_state: &mut GameState) -> Result<(), EngineError> {
+    let root = game_state
+        .ecs
+        .world
+        .read_resource::<AssetCache>()
+        .root
+        .clone()
+        .ok_or_else(|| notify::Error::Generic("No assets folder found to watch".to_string()))?;
+    game_state.ecs.asset_watcher = Some(AssetWatcher::new(&root)?);
+    Ok(())
+}
+
+pub fn save_bindings<P: AsRef<Path>>(game_state: &GameState, path: P) -> Result<(), EngineError> {
+    game_state
+        .ecs
+        .world
+        .read_resource::<KeyBindings>()
+        .save(path)?;
+    Ok(())
+}
+
+impl ECS<'_, '_> {
+    /// Runs a single simulation tick: the physics step followed by the gameplay systems.
+    fn tick(&mut self, delta: f64) {
         {
             // Scoped so the pointer is thrown out as soon as it's no longer useful
-            let mut delta = self.world.write_resource::<DeltaTime>();
-            *delta = DeltaTime(timer::delta(context).as_secs_f64());
+            let mut delta_time = self.world.write_resource::<DeltaTime>();
+            *delta_time = DeltaTime(delta);
+        }
+
+        {
+            let camera = self.world.read_resource::<Camera>();
+            let mut input_context = self.world.write_resource::<InputContext>();
+            let (x, y) = input_context.mouse_position;
+            let world_position = camera.screen_to_world(&Point2::new(x as f64, y as f64));
+            input_context.mouse_world_position = (world_position.x, world_position.y);
         }
+
+        // After the world position, which depends on how far the camera moved in the frames drawn,
+        // so replays use the recorded one
+        self.world
+            .write_resource::<InputReplay>()
+            .process(&mut self.world.write_resource::<InputContext>());
+
         {
-            let mut input = InputContext {
-                pressed_keys: pressed_keys(context).clone(),
-                last_pressed_keys: HashSet::new(),
-                active_mods: active_mods(context),
-                mouse_context: context.mouse_context.clone(),
-            };
-            if !self.world.has_value::<InputContext>() {
-                self.world.insert(input);
-            } else {
-                let mut input_context = self.world.write_resource::<InputContext>();
-                input.last_pressed_keys = input_context.pressed_keys.clone();
-                *input_context = input;
+            let bodies = self.world.read_resource::<MyBodySet>();
+            let transforms = self.world.read_storage::<TransformComponent>();
+            let mut previous_transforms = self.world.write_storage::<PreviousTransform>();
+            for (transform, previous) in (&transforms, &mut previous_transforms).join() {
+                if let Some(body) = bodies.0.rigid_body(transform.0) {
+                    previous.0 = *body.position();
+                }
             }
         }
 
@@ -149,6 +378,22 @@ impl EventHandler for ECS<'_, '_> {
             let mut joint_constraints = self.world.write_resource::<MyJointConstraintSet>();
             let mut force_generators = self.world.write_resource::<MyForceGeneratorSet>();
 
+            // nphysics can only turn gravity on or off per body, so scaled gravity is applied here
+            let gravity = mechanical_world.0.gravity;
+            let transforms = self.world.read_storage::<TransformComponent>();
+            let gravity_scales = self.world.read_storage::<GravityScale>();
+            for (transform, gravity_scale) in (&transforms, &gravity_scales).join() {
+                if let Some(body) = bodies.0.rigid_body_mut(transform.0) {
+                    body.apply_force(
+                        0,
+                        &Force::linear(gravity * gravity_scale.0),
+                        ForceType::AccelerationChange,
+                        false,
+                    );
+                }
+            }
+
+            mechanical_world.0.set_timestep(delta);
             mechanical_world.0.step(
                 &mut geometrical_world.0,
                 &mut bodies.0,
@@ -160,13 +405,191 @@ impl EventHandler for ECS<'_, '_> {
 
         self.dispatcher.dispatch(&mut self.world);
         self.world.maintain();
+        remove_orphaned_bodies(&self.world);
+        remove_orphaned_joints(&self.world);
+        remove_orphaned_force_generators(&self.world);
+
+        // Presses are edges between ticks, not frames, so a frame running several ticks only
+        // reports them once
+        self.world.write_resource::<InputContext>().end_tick();
+    }
+}
+
+/// Removes bodies whose entity was deleted without `destroy_entity`, such as by a system, so they
+/// stop colliding. The mechanical world removes their colliders along with them. Bodies added to
+/// `MyBodySet` directly are left alone.
+fn remove_orphaned_bodies(world: &World) {
+    let entities = world.entities();
+    let mut entity_bodies = world.write_resource::<EntityBodies>();
+    let mut bodies = world.write_resource::<MyBodySet>();
+    entity_bodies.0.retain(|entity, handle| {
+        if entities.is_alive(*entity) {
+            return true;
+        }
+        bodies.0.remove(*handle);
+        false
+    });
+}
+
+/// Deletes joints connecting an entity that no longer exists. nphysics removes their constraints
+/// along with the bodies, this removes the entities holding them. Constraints of joint entities
+/// that were deleted themselves are removed too, those added to `MyJointConstraintSet` directly
+/// are left alone.
+fn remove_orphaned_joints(world: &World) {
+    let entities = world.entities();
+    let joints = world.read_storage::<JointComponent>();
+    let mut entity_joints = world.write_resource::<EntityJoints>();
+    let mut constraints = world.write_resource::<MyJointConstraintSet>();
+    entity_joints.0.retain(|entity, handle| {
+        let referenced = match joints.get(*entity) {
+            Some(JointComponent {
+                joint: Joint::Constraint(joint_handle),
+                ..
+            }) => joint_handle == handle,
+            _ => false,
+        };
+        if referenced {
+            return true;
+        }
+        constraints.0.remove(*handle);
+        false
+    });
+    for (entity, joint) in (&entities, &joints).join() {
+        let connected = entities.is_alive(joint.entity1) && entities.is_alive(joint.entity2);
+        let constraint_exists = match joint.joint {
+            Joint::Constraint(handle) => constraints.0.contains(handle),
+            Joint::Rope { .. } => true,
+        };
+        if connected && constraint_exists {
+            continue;
+        }
+        if let Joint::Constraint(handle) = joint.joint {
+            constraints.0.remove(handle);
+        }
+        if let Err(e) = entities.delete(entity) {
+            log::warn!("Failed removing joint of deleted entity: {}", e);
+        }
+    }
+}
+
+/// Removes the engine's force generators once their entities or bodies are gone. Generators added
+/// to `MyForceGeneratorSet` directly are left alone.
+fn remove_orphaned_force_generators(world: &World) {
+    let components = world.read_storage::<ForceGeneratorComponent>();
+    let referenced: HashSet<DefaultForceGeneratorHandle> = (&components)
+        .join()
+        .flat_map(|component| component.0.iter().cloned())
+        .collect();
+    let bodies = world.read_resource::<MyBodySet>();
+    let mut force_generators = world.write_resource::<MyForceGeneratorSet>();
+    let orphaned: Vec<DefaultForceGeneratorHandle> = force_generators
+        .0
+        .iter()
+        .filter(
+            |(handle, force_generator)| match force_generator.downcast_ref::<ForceField>() {
+                Some(force_field) => {
+                    !referenced.contains(handle)
+                        || force_field
+                            .bodies()
+                            .iter()
+                            .any(|body| bodies.0.get(*body).is_none())
+                }
+                None => false,
+            },
+        )
+        .map(|(handle, _)| handle)
+        .collect();
+    for handle in orphaned {
+        force_generators.0.remove(handle);
+    }
+}
+
+impl EventHandler for ECS<'_, '_> {
+    fn update(&mut self, context: &mut Context) -> GameResult<()> {
+        if let Some(asset_watcher) = &self.asset_watcher {
+            reload_changed(&mut self.world, context, asset_watcher);
+        }
+        load_animation_atlases(&self.world, context);
+
+        {
+            // Gamepad state is kept up to date by the gamepad events instead
+            let mouse_position = mouse::position(context);
+            let mut input_context = self.world.write_resource::<InputContext>();
+            input_context.pressed_keys = pressed_keys(context).clone();
+            input_context.active_mods = active_mods(context);
+            input_context.mouse_position = (mouse_position.x, mouse_position.y);
+            input_context.mouse_buttons = [
+                mouse::MouseButton::Left,
+                mouse::MouseButton::Right,
+                mouse::MouseButton::Middle,
+            ]
+            .iter()
+            .cloned()
+            .filter(|button| mouse::button_pressed(context, *button))
+            .collect();
+        }
+
+        let (ticks, step) = {
+            let mut timestep = self.world.write_resource::<FixedTimestep>();
+            (
+                timestep.advance(timer::delta(context).as_secs_f64()),
+                timestep.step,
+            )
+        };
+        for _ in 0..ticks {
+            self.tick(step);
+        }
         Ok(())
     }
 
+    fn gamepad_button_down_event(
+        &mut self,
+        _context: &mut Context,
+        button: Button,
+        _id: GamepadId,
+    ) {
+        self.world
+            .write_resource::<InputContext>()
+            .gamepad_buttons
+            .insert(button);
+    }
+
+    fn gamepad_button_up_event(&mut self, _context: &mut Context, button: Button, _id: GamepadId) {
+        self.world
+            .write_resource::<InputContext>()
+            .gamepad_buttons
+            .remove(&button);
+    }
+
+    fn gamepad_axis_event(
+        &mut self,
+        _context: &mut Context,
+        axis: Axis,
+        value: f32,
+        _id: GamepadId,
+    ) {
+        self.world
+            .write_resource::<InputContext>()
+            .gamepad_axes
+            .insert(axis, value);
+    }
+
+    fn resize_event(&mut self, context: &mut Context, width: f32, height: f32) {
+        // Keep drawing at one pixel per unit instead of stretching the view to the window
+        if let Err(e) =
+            graphics::set_screen_coordinates(context, graphics::Rect::new(0.0, 0.0, width, height))
+        {
+            log::warn!("Failed resizing screen coordinates: {}", e);
+        }
+        self.world.write_resource::<Camera>().viewport = Vector2::new(width as f64, height as f64);
+    }
+
     fn draw(&mut self, context: &mut Context) -> GameResult<()> {
         graphics::clear(context, graphics::BLACK);
 
         {
+            let mut camera_system = CameraSystem::new(timer::delta(context).as_secs_f64());
+            camera_system.run_now(&self.world);
             let mut draw_system = DrawSystem::new(context);
             draw_system.run_now(&mut self.world);
         }
@@ -181,74 +604,516 @@ pub fn create_entity<'a>(
     y: f64,
     rotation: f64,
 ) -> EntityBuilder<'a> {
-    let body = RigidBodyDesc::new()
-        .translation(Vector2::new(x, y))
-        .rotation(rotation)
-        .linear_damping(100.0)
-        .build();
+    create_entity_with(game_state, x, y, rotation, &BodyDescriptor::default())
+}
+
+/// Creates an entity with a body built from the descriptor, such as a static body for a wall.
+pub fn create_entity_with<'a>(
+    game_state: &'a mut GameState,
+    x: f64,
+    y: f64,
+    rotation: f64,
+    descriptor: &BodyDescriptor,
+) -> EntityBuilder<'a> {
+    create_entity_in(&mut game_state.ecs.world, x, y, rotation, descriptor)
+}
+
+pub(crate) fn create_entity_in<'a>(
+    world: &'a mut World,
+    x: f64,
+    y: f64,
+    rotation: f64,
+    descriptor: &BodyDescriptor,
+) -> EntityBuilder<'a> {
+    let body = descriptor.build(x, y, rotation);
+    let previous_transform = PreviousTransform(*body.position());
 
     let transform: TransformComponent;
 
     {
-        let mut body_set = game_state.ecs.world.write_resource::<MyBodySet>();
+        let mut body_set = world.write_resource::<MyBodySet>();
         transform = TransformComponent {
             0: body_set.0.insert(body),
         };
     }
+    let body_handle = transform.0;
+
+    let mut builder = world
+        .create_entity()
+        .with(transform)
+        .with(previous_transform)
+        .with(BodyMass(descriptor.mass));
+    builder
+        .world
+        .write_resource::<EntityBodies>()
+        .0
+        .insert(builder.entity, body_handle);
+    if descriptor.has_custom_gravity() {
+        builder = builder.with(GravityScale(descriptor.gravity_scale));
+    }
+    builder
+}
 
-    game_state.ecs.world.create_entity().with(transform)
+/// Deletes the entity along with its body and colliders.
+pub fn destroy_entity(game_state: &mut GameState, entity: Entity) -> Result<(), EngineError> {
+    destroy_entity_in(&mut game_state.ecs.world, entity)
 }
 
-pub fn add_collider<'a>(
-    game_state: &mut GameState,
-    entity: Entity,
-    shape: ShapeHandle<f64>,
-) -> DefaultColliderHandle {
-    let mut collider_set = game_state.ecs.world.write_resource::<MyColliderSet>();
-    let body_handle = game_state
+pub(crate) fn destroy_entity_in(world: &mut World, entity: Entity) -> Result<(), EngineError> {
+    if let Some(collider_component) = world.write_storage::<ColliderComponent>().remove(entity) {
+        let mut colliders = world.write_resource::<MyColliderSet>();
+        for collider_handle in collider_component.0 {
+            colliders.0.remove(collider_handle);
+        }
+    }
+    if let Some(transform) = world.write_storage::<TransformComponent>().remove(entity) {
+        world.write_resource::<MyBodySet>().0.remove(transform.0);
+    }
+    world.write_resource::<EntityBodies>().0.remove(&entity);
+    if let Some(force_generators) = world
+        .write_storage::<ForceGeneratorComponent>()
+        .remove(entity)
+    {
+        let mut force_generator_set = world.write_resource::<MyForceGeneratorSet>();
+        for handle in force_generators.0 {
+            force_generator_set.0.remove(handle);
+        }
+    }
+    if let Some(joint) = world.write_storage::<JointComponent>().remove(entity) {
+        if let Joint::Constraint(handle) = joint.joint {
+            world
+                .write_resource::<MyJointConstraintSet>()
+                .0
+                .remove(handle);
+        }
+    }
+    world.write_resource::<EntityJoints>().0.remove(&entity);
+    world.delete_entity(entity)?;
+    Ok(())
+}
+
+/// Sets the acceleration applied to every dynamic body. The world starts out without gravity.
+pub fn set_gravity(game_state: &mut GameState, gravity: Vector2<f64>) {
+    game_state
+        .ecs
+        .world
+        .write_resource::<MyMechanicalWorld>()
+        .0
+        .gravity = gravity;
+}
+
+pub fn gravity(game_state: &GameState) -> Vector2<f64> {
+    game_state
         .ecs
         .world
+        .read_resource::<MyMechanicalWorld>()
+        .0
+        .gravity
+}
+
+/// Makes the entity a gravity well, pulling dynamic bodies within the radius towards it. Negative
+/// strength pushes them away instead.
+pub fn add_attractor(
+    game_state: &mut GameState,
+    entity: Entity,
+    strength: f64,
+    radius: f64,
+) -> Result<DefaultForceGeneratorHandle, EngineError> {
+    let world = &game_state.ecs.world;
+    let source = body_handle(world, entity)?;
+    add_force_field(
+        world,
+        &[entity],
+        ForceField::Radial {
+            source,
+            strength,
+            radius,
+        },
+    )
+}
+
+/// Accelerates dynamic bodies inside the box around the entity, like wind or a current.
+pub fn add_wind_zone(
+    game_state: &mut GameState,
+    entity: Entity,
+    half_extents: Vector2<f64>,
+    acceleration: Vector2<f64>,
+) -> Result<DefaultForceGeneratorHandle, EngineError> {
+    let world = &game_state.ecs.world;
+    let source = body_handle(world, entity)?;
+    add_force_field(
+        world,
+        &[entity],
+        ForceField::Wind {
+            source,
+            half_extents,
+            acceleration,
+        },
+    )
+}
+
+/// Connects the bodies of two entities with a damped spring, removed along with either entity.
+pub fn add_spring(
+    game_state: &mut GameState,
+    entity1: Entity,
+    entity2: Entity,
+    spring: &SpringDescriptor,
+) -> Result<DefaultForceGeneratorHandle, EngineError> {
+    let world = &game_state.ecs.world;
+    let body1 = body_handle(world, entity1)?;
+    let body2 = body_handle(world, entity2)?;
+    add_force_field(
+        world,
+        &[entity1, entity2],
+        ForceField::Spring {
+            body1,
+            body2,
+            spring: *spring,
+        },
+    )
+}
+
+/// Looks up the entity's body, making sure it still exists before physics objects are attached.
+fn body_handle(world: &World, entity: Entity) -> Result<DefaultBodyHandle, EngineError> {
+    let handle = world
         .read_component::<TransformComponent>()
         .get(entity)
-        .expect("Attempted to add collider to entity without transform!")
+        .ok_or(EngineError::NoBody(entity))?
         .0;
+    match world.read_resource::<MyBodySet>().0.get(handle) {
+        Some(_) => Ok(handle),
+        None => Err(EngineError::StaleBody(handle)),
+    }
+}
 
-    let collider = ColliderDesc::new(shape)
-        .ccd_enabled(true)
-        .material(MaterialHandle::new(BasicMaterial::new(1.0, 0.2)))
-        .build(BodyPartHandle(body_handle, 0));
+fn add_force_field(
+    world: &World,
+    entities: &[Entity],
+    force_field: ForceField,
+) -> Result<DefaultForceGeneratorHandle, EngineError> {
+    if let Some(entity) = entities.iter().find(|entity| !world.is_alive(**entity)) {
+        return Err(EngineError::DeadEntity(*entity));
+    }
+    let handle = world
+        .write_resource::<MyForceGeneratorSet>()
+        .0
+        .insert(Box::new(force_field));
+    let mut components = world.write_component::<ForceGeneratorComponent>();
+    for entity in entities {
+        match components.get_mut(*entity) {
+            Some(component) => component.0.push(handle),
+            None => {
+                components
+                    .insert(*entity, ForceGeneratorComponent(vec![handle]))
+                    .map_err(|_| EngineError::DeadEntity(*entity))?;
+            }
+        }
+    }
+    Ok(handle)
+}
+
+/// Connects the bodies of two entities, returning the entity holding the joint. Destroy it to
+/// remove the joint, which also happens when either connected entity is destroyed.
+pub fn add_joint(
+    game_state: &mut GameState,
+    entity1: Entity,
+    entity2: Entity,
+    descriptor: &JointDescriptor,
+) -> Result<Entity, EngineError> {
+    let world = &mut game_state.ecs.world;
+    let joint_entity = world.create_entity().build();
+    if let Err(e) = insert_joint(world, joint_entity, entity1, entity2, descriptor) {
+        let _ = world.delete_entity(joint_entity);
+        return Err(e);
+    }
+    Ok(joint_entity)
+}
 
-    let collider_component = ColliderComponent {
-        0: collider_set.0.insert(collider),
+/// Starts dragging the entity by the given point, in world space, towards a static anchor that
+/// `move_drag` moves around. Destroy the returned anchor to let go.
+pub fn start_drag(
+    game_state: &mut GameState,
+    entity: Entity,
+    point: Point2<f64>,
+    max_force: f64,
+) -> Result<Entity, EngineError> {
+    let world = &mut game_state.ecs.world;
+    let body_position = {
+        let body_handle = body_handle(world, entity)?;
+        *world
+            .read_resource::<MyBodySet>()
+            .0
+            .rigid_body(body_handle)
+            .ok_or(EngineError::StaleBody(body_handle))?
+            .position()
     };
-    game_state
-        .ecs
-        .world
-        .write_component::<ColliderComponent>()
+    let anchor = create_entity_in(world, point.x, point.y, 0.0, &BodyDescriptor::fixed()).build();
+    let joint = insert_joint(
+        world,
+        anchor,
+        anchor,
+        entity,
+        &JointDescriptor::Mouse {
+            anchor1: Point2::origin(),
+            anchor2: body_position.inverse_transform_point(&point),
+            max_force,
+        },
+    );
+    if let Err(e) = joint {
+        let _ = destroy_entity_in(world, anchor);
+        return Err(e);
+    }
+    Ok(anchor)
+}
+
+/// Moves the anchor of a drag started with `start_drag`.
+pub fn move_drag(game_state: &mut GameState, anchor: Entity, point: Point2<f64>) {
+    let world = &game_state.ecs.world;
+    if let Some(transform) = world.read_component::<TransformComponent>().get(anchor) {
+        if let Some(body) = world
+            .write_resource::<MyBodySet>()
+            .0
+            .rigid_body_mut(transform.0)
+        {
+            body.set_position(Isometry2::translation(point.x, point.y));
+        }
+    }
+}
+
+fn insert_joint(
+    world: &mut World,
+    joint_entity: Entity,
+    entity1: Entity,
+    entity2: Entity,
+    descriptor: &JointDescriptor,
+) -> Result<(), EngineError> {
+    let body1 = body_handle(world, entity1)?;
+    let body2 = body_handle(world, entity2)?;
+    let joint = descriptor.build(
+        &mut world.write_resource::<MyJointConstraintSet>().0,
+        body1,
+        body2,
+    );
+    if let Joint::Constraint(handle) = joint {
+        world
+            .write_resource::<EntityJoints>()
+            .0
+            .insert(joint_entity, handle);
+    }
+    world
+        .write_component::<JointComponent>()
         .insert(
-            entity,
-            ColliderComponent {
-                ..collider_component
+            joint_entity,
+            JointComponent {
+                entity1,
+                entity2,
+                joint,
             },
         )
-        .expect("Failed to add collider component!");
-    collider_component.0
+        .map_err(|_| EngineError::DeadEntity(joint_entity))?;
+    Ok(())
 }
 
-pub fn run(game_state: &mut GameState) {
-    match event::run(
-        &mut game_state.context,
-        &mut game_state.event_loop,
-        &mut game_state.ecs,
-    ) {
-        Ok(_) => println!("Game exited cleanly"),
-        Err(e) => println!("Error occurred: {}", e),
+pub fn add_collider<'a>(
+    game_state: &mut GameState,
+    entity: Entity,
+    shape: ShapeHandle<f64>,
+) -> Result<DefaultColliderHandle, EngineError> {
+    add_collider_with_material(game_state, entity, shape, 1.0, 0.2)
+}
+
+pub fn add_collider_with_material(
+    game_state: &mut GameState,
+    entity: Entity,
+    shape: ShapeHandle<f64>,
+    restitution: f64,
+    friction: f64,
+) -> Result<DefaultColliderHandle, EngineError> {
+    add_collider_with(
+        game_state,
+        entity,
+        &ColliderDescriptor::new(shape).material(restitution, friction),
+    )
+}
+
+/// Adds a sensor collider, which never pushes bodies but reports `ProximityEntered` and
+/// `ProximityExited` collision events for entities overlapping it. Useful for pickups, doors and
+/// zone transitions.
+pub fn add_trigger(
+    game_state: &mut GameState,
+    entity: Entity,
+    shape: ShapeHandle<f64>,
+) -> Result<DefaultColliderHandle, EngineError> {
+    add_collider_with(
+        game_state,
+        entity,
+        &ColliderDescriptor::new(shape).sensor(true),
+    )
+}
+
+/// Adds a collider built from the descriptor. Entities can have any number of colliders, such as
+/// a hurtbox and a sensor at its feet.
+pub fn add_collider_with(
+    game_state: &mut GameState,
+    entity: Entity,
+    descriptor: &ColliderDescriptor,
+) -> Result<DefaultColliderHandle, EngineError> {
+    add_collider_in(&game_state.ecs.world, entity, descriptor)
+}
+
+pub(crate) fn add_collider_in(
+    world: &World,
+    entity: Entity,
+    descriptor: &ColliderDescriptor,
+) -> Result<DefaultColliderHandle, EngineError> {
+    let body_handle = body_handle(world, entity)?;
+    if !world.is_alive(entity) {
+        return Err(EngineError::DeadEntity(entity));
+    }
+    let collider_handle = descriptor.insert(
+        &mut world.write_resource::<MyColliderSet>().0,
+        body_handle,
+        entity,
+    );
+
+    let mut collider_storage = world.write_component::<ColliderComponent>();
+    match collider_storage.get_mut(entity) {
+        Some(collider_component) => collider_component.0.push(collider_handle),
+        None => {
+            collider_storage
+                .insert(entity, ColliderComponent(vec![collider_handle]))
+                .map_err(|_| EngineError::DeadEntity(entity))?;
+        }
     }
+    Ok(collider_handle)
 }
 
-pub fn load_image(game_state: &mut GameState, filename: &str) -> graphics::Image {
-    graphics::Image::new(&mut game_state.context, filename).expect(&format!(
-        "Failed loading image with file name: {}",
-        filename
-    ))
+/// Runs the game until its window is closed.
+pub fn run(game_state: &mut GameState) -> Result<(), EngineError> {
+    let (context, event_loop) = match (&mut game_state.context, &mut game_state.event_loop) {
+        (Some(context), Some(event_loop)) => (context, event_loop),
+        _ => return Err(EngineError::Headless),
+    };
+    event::run(context, event_loop, &mut game_state.ecs).map_err(EngineError::Context)
+}
+
+/// Loads an image from the assets folder, or returns the already loaded one. Paths are virtual
+/// paths inside the assets folder, with either kind of slash.
+pub fn try_load_image(
+    game_state: &mut GameState,
+    filename: &str,
+) -> Result<graphics::Image, EngineError> {
+    let context = game_state.context.as_mut().ok_or(AssetError::NoContext)?;
+    Ok(game_state
+        .ecs
+        .world
+        .write_resource::<AssetCache>()
+        .image(context, filename)?)
+}
+
+/// Like `try_load_image`, but falls back to a placeholder texture if the image can't be loaded.
+/// Only fails without a window or if the placeholder can't be created either.
+pub fn load_image(
+    game_state: &mut GameState,
+    filename: &str,
+) -> Result<graphics::Image, EngineError> {
+    let context = game_state.context.as_mut().ok_or(AssetError::NoContext)?;
+    Ok(load_image_in(&game_state.ecs.world, context, filename)?)
+}
+
+/// Starts animating the entity's sprite with frames from the atlas of its image. The sprite can be
+/// added or replaced later, its atlas is loaded before the next tick.
+pub fn add_animation(
+    game_state: &mut GameState,
+    entity: Entity,
+    animation: Animation,
+) -> Result<(), EngineError> {
+    game_state
+        .ecs
+        .world
+        .write_component::<Animation>()
+        .insert(entity, animation)
+        .map_err(|_| EngineError::DeadEntity(entity))?;
+    Ok(())
+}
+
+/// Loads the atlases of animated sprites, which the animation system has no context to do during
+/// a tick. Atlases that fail to load are replaced by an empty one so they're only reported once.
+fn load_animation_atlases(world: &World, context: &mut Context) {
+    let animations = world.read_storage::<Animation>();
+    let sprites = world.read_storage::<Sprite>();
+    let mut asset_cache = world.write_resource::<AssetCache>();
+    for (_, sprite) in (&animations, &sprites).join() {
+        if asset_cache.is_atlas_loaded(&sprite.path) {
+            continue;
+        }
+        if let Err(e) = asset_cache.atlas(context, &sprite.path) {
+            log::warn!("{}, animation frames can't be shown", e);
+            asset_cache.insert_atlas(&sprite.path, TextureAtlas::default());
+        }
+    }
+}
+
+/// Loads a sprite showing the named frame of the image's atlas, or the whole image without one.
+/// Falls back to a placeholder like `load_image` if either can't be loaded.
+pub fn load_sprite(
+    game_state: &mut GameState,
+    filename: &str,
+    frame: Option<&str>,
+) -> Result<Sprite, EngineError> {
+    let context = game_state.context.as_mut().ok_or(AssetError::NoContext)?;
+    Ok(load_sprite_in(
+        &game_state.ecs.world,
+        context,
+        filename,
+        frame,
+    )?)
+}
+
+pub(crate) fn load_sprite_in(
+    world: &World,
+    context: &mut Context,
+    filename: &str,
+    frame: Option<&str>,
+) -> Result<Sprite, AssetError> {
+    let mut asset_cache = world.write_resource::<AssetCache>();
+    let loaded = asset_cache
+        .image(context, filename)
+        .and_then(|image| match frame {
+            Some(frame) => Ok((image, asset_cache.frame(context, filename, frame)?)),
+            None => Ok((image, graphics::Rect::one())),
+        });
+    let (image, texture, src) = match loaded {
+        Ok((image, src)) => (image, normalize_path(filename), src),
+        Err(e) => {
+            log::warn!("{}, using placeholder", e);
+            (
+                asset_cache.placeholder(context)?,
+                PLACEHOLDER_PATH.to_string(),
+                graphics::Rect::one(),
+            )
+        }
+    };
+    Ok(Sprite {
+        image,
+        path: normalize_path(filename),
+        texture,
+        frame: frame.map(|frame| frame.to_string()),
+        src,
+    })
+}
+
+pub(crate) fn load_image_in(
+    world: &World,
+    context: &mut Context,
+    filename: &str,
+) -> Result<graphics::Image, AssetError> {
+    let mut asset_cache = world.write_resource::<AssetCache>();
+    match asset_cache.image(context, filename) {
+        Ok(image) => Ok(image),
+        Err(e) => {
+            log::warn!("{}, using placeholder", e);
+            asset_cache.placeholder(context)
+        }
+    }
 }
@@ -0,0 +1,157 @@
+use nalgebra::Vector2;
+use nphysics2d::object::ActivationStatus;
+use nphysics2d::object::Body;
+use nphysics2d::object::BodyPart;
+use nphysics2d::object::BodyStatus;
+use nphysics2d::object::RigidBody;
+use nphysics2d::object::RigidBodyDesc;
+use serde::{Deserialize, Serialize};
+
+/// How a body takes part in the simulation.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyType {
+    /// Never moves, for walls and floors.
+    Static,
+    /// Moves only by its velocity being set, unaffected by forces and contacts.
+    Kinematic,
+    /// Moved by forces, gravity and contacts.
+    Dynamic,
+}
+
+impl From<BodyType> for BodyStatus {
+    fn from(body_type: BodyType) -> Self {
+        match body_type {
+            BodyType::Static => BodyStatus::Static,
+            BodyType::Kinematic => BodyStatus::Kinematic,
+            BodyType::Dynamic => BodyStatus::Dynamic,
+        }
+    }
+}
+
+impl From<BodyStatus> for BodyType {
+    fn from(status: BodyStatus) -> Self {
+        match status {
+            BodyStatus::Static | BodyStatus::Disabled => BodyType::Static,
+            BodyStatus::Kinematic => BodyType::Kinematic,
+            BodyStatus::Dynamic => BodyType::Dynamic,
+        }
+    }
+}
+
+/// Describes the rigid body an entity is created with. The default is a dynamic body with a mass
+/// of 1 and heavy linear damping, so it stops as soon as it's no longer pushed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct BodyDescriptor {
+    pub body_type: BodyType,
+    pub mass: f64,
+    pub linear_damping: f64,
+    pub angular_damping: f64,
+    /// Multiplies the world's gravity for this body, 0 to ignore it.
+    pub gravity_scale: f64,
+    /// Keeps the body from rotating in response to contacts and forces.
+    pub lock_rotation: bool,
+    /// Kinetic energy below which the body falls asleep, or `None` to keep it always awake.
+    pub sleep_threshold: Option<f64>,
+}
+
+impl Default for BodyDescriptor {
+    fn default() -> Self {
+        BodyDescriptor {
+            body_type: BodyType::Dynamic,
+            mass: 1.0,
+            linear_damping: 100.0,
+            angular_damping: 0.0,
+            gravity_scale: 1.0,
+            lock_rotation: false,
+            sleep_threshold: Some(ActivationStatus::<f64>::default_threshold()),
+        }
+    }
+}
+
+impl BodyDescriptor {
+    pub fn new() -> BodyDescriptor {
+        BodyDescriptor::default()
+    }
+
+    /// A static body, for walls and floors.
+    pub fn fixed() -> BodyDescriptor {
+        BodyDescriptor::new().body_type(BodyType::Static)
+    }
+
+    pub fn body_type(mut self, body_type: BodyType) -> Self {
+        self.body_type = body_type;
+        self
+    }
+
+    pub fn mass(mut self, mass: f64) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    pub fn damping(mut self, linear_damping: f64, angular_damping: f64) -> Self {
+        self.linear_damping = linear_damping;
+        self.angular_damping = angular_damping;
+        self
+    }
+
+    pub fn gravity_scale(mut self, gravity_scale: f64) -> Self {
+        self.gravity_scale = gravity_scale;
+        self
+    }
+
+    pub fn lock_rotation(mut self, lock_rotation: bool) -> Self {
+        self.lock_rotation = lock_rotation;
+        self
+    }
+
+    pub fn sleep_threshold(mut self, sleep_threshold: Option<f64>) -> Self {
+        self.sleep_threshold = sleep_threshold;
+        self
+    }
+
+    /// Bodies with a gravity scale other than 1 have the scaled gravity applied by the engine
+    /// instead of nphysics, which only knows whether gravity is on or off.
+    pub(crate) fn has_custom_gravity(&self) -> bool {
+        self.gravity_scale != 1.0
+    }
+
+    pub(crate) fn build(&self, x: f64, y: f64, rotation: f64) -> RigidBody<f64> {
+        let mut body = RigidBodyDesc::new()
+            .translation(Vector2::new(x, y))
+            .rotation(rotation)
+            .build();
+        self.apply(&mut body);
+        body
+    }
+
+    /// Reconfigures an existing body, keeping its position and velocity.
+    pub(crate) fn apply(&self, body: &mut RigidBody<f64>) {
+        body.set_status(self.body_type.into());
+        body.set_mass(self.mass);
+        body.set_linear_damping(self.linear_damping);
+        body.set_angular_damping(self.angular_damping);
+        body.enable_gravity(!self.has_custom_gravity());
+        body.set_rotations_kinematic(self.lock_rotation);
+        body.set_deactivation_threshold(self.sleep_threshold);
+    }
+
+    /// Describes an existing body, given the mass it was created with and the gravity scale the
+    /// engine applies to it. Without a creation mass the body's current mass is used, which
+    /// includes the mass of its colliders.
+    pub fn from_body(
+        body: &RigidBody<f64>,
+        mass: Option<f64>,
+        gravity_scale: Option<f64>,
+    ) -> BodyDescriptor {
+        BodyDescriptor {
+            body_type: body.status().into(),
+            mass: mass.unwrap_or_else(|| body.local_inertia().linear),
+            linear_damping: body.linear_damping(),
+            angular_damping: body.angular_damping(),
+            gravity_scale: gravity_scale.unwrap_or(1.0),
+            lock_rotation: body.kinematic_rotations(),
+            sleep_threshold: body.activation_status().deactivation_threshold(),
+        }
+    }
+}
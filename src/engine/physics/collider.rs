@@ -0,0 +1,114 @@
+use nalgebra::Vector2;
+use ncollide2d::pipeline::CollisionGroups;
+use ncollide2d::shape::ShapeHandle;
+use nphysics2d::material::BasicMaterial;
+use nphysics2d::material::MaterialHandle;
+use nphysics2d::math::Isometry;
+use nphysics2d::object::BodyPartHandle;
+use nphysics2d::object::ColliderDesc;
+use nphysics2d::object::DefaultBodyHandle;
+use nphysics2d::object::DefaultColliderHandle;
+use nphysics2d::object::DefaultColliderSet;
+use specs::Entity;
+
+/// Describes a collider to attach to an entity's body. Start from `new` and chain the setters for
+/// whatever differs from the defaults: a solid collider centered on the body with restitution 1.0,
+/// friction 0.2, no density, continuous collision detection and interacting with every group.
+#[derive(Clone)]
+pub struct ColliderDescriptor {
+    pub shape: ShapeHandle<f64>,
+    pub restitution: f64,
+    pub friction: f64,
+    /// Mass per unit area, added to the body's mass.
+    pub density: f64,
+    /// Position and rotation relative to the body.
+    pub offset: Isometry<f64>,
+    pub collision_groups: CollisionGroups,
+    pub sensor: bool,
+    pub ccd_enabled: bool,
+}
+
+impl ColliderDescriptor {
+    pub fn new(shape: ShapeHandle<f64>) -> ColliderDescriptor {
+        ColliderDescriptor {
+            shape,
+            restitution: 1.0,
+            friction: 0.2,
+            density: 0.0,
+            offset: Isometry::identity(),
+            collision_groups: CollisionGroups::new(),
+            sensor: false,
+            ccd_enabled: true,
+        }
+    }
+
+    pub fn material(mut self, restitution: f64, friction: f64) -> Self {
+        self.restitution = restitution;
+        self.friction = friction;
+        self
+    }
+
+    pub fn density(mut self, density: f64) -> Self {
+        self.density = density;
+        self
+    }
+
+    pub fn offset(mut self, x: f64, y: f64, rotation: f64) -> Self {
+        self.offset = Isometry::new(Vector2::new(x, y), rotation);
+        self
+    }
+
+    /// The groups, from 0 to 29, this collider is part of.
+    pub fn membership(mut self, groups: &[usize]) -> Self {
+        self.collision_groups.set_membership(groups);
+        self
+    }
+
+    /// The only groups this collider interacts with.
+    pub fn whitelist(mut self, groups: &[usize]) -> Self {
+        self.collision_groups.set_whitelist(groups);
+        self
+    }
+
+    /// Groups this collider never interacts with, even if whitelisted.
+    pub fn blacklist(mut self, groups: &[usize]) -> Self {
+        self.collision_groups.set_blacklist(groups);
+        self
+    }
+
+    /// Sensors never push bodies, they report overlapping entities as proximity events instead.
+    /// Continuous collision detection is turned off for them.
+    pub fn sensor(mut self, sensor: bool) -> Self {
+        self.sensor = sensor;
+        self.ccd_enabled = !sensor;
+        self
+    }
+
+    pub fn ccd_enabled(mut self, ccd_enabled: bool) -> Self {
+        self.ccd_enabled = ccd_enabled;
+        self
+    }
+
+    /// Builds the collider onto the body. The collider is tagged with the entity owning the body,
+    /// so physics queries can tell which entity they found.
+    pub(crate) fn insert(
+        &self,
+        collider_set: &mut DefaultColliderSet<f64>,
+        body_handle: DefaultBodyHandle,
+        entity: Entity,
+    ) -> DefaultColliderHandle {
+        let mut collider = ColliderDesc::new(self.shape.clone())
+            .material(MaterialHandle::new(BasicMaterial::new(
+                self.restitution,
+                self.friction,
+            )))
+            .density(self.density)
+            .position(self.offset)
+            .collision_groups(self.collision_groups)
+            .sensor(self.sensor)
+            .ccd_enabled(self.ccd_enabled)
+            .build(BodyPartHandle(body_handle, 0));
+        collider.set_user_data(Some(Box::new(entity)));
+        collider_set.insert(collider)
+    }
+}
@@ -0,0 +1,13 @@
+use specs::Entity;
+
+/// A change in how two entities' colliders touch, written to the `EventChannel<CollisionEvent>`
+/// resource after every physics step. Gameplay systems read them by registering a reader in
+/// their `setup`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionEvent {
+    ContactStarted(Entity, Entity),
+    ContactStopped(Entity, Entity),
+    /// Reported for sensors, which detect overlap without generating contacts.
+    ProximityEntered(Entity, Entity),
+    ProximityExited(Entity, Entity),
+}
@@ -0,0 +1,174 @@
+use nalgebra::{Point2, Vector2};
+use nphysics2d::force_generator::ForceGenerator;
+use nphysics2d::math::{Force, ForceType, Isometry, Velocity};
+use nphysics2d::object::{Body, BodyPart, BodySet, BodyStatus, DefaultBodyHandle};
+use nphysics2d::solver::IntegrationParameters;
+
+/// Describes a damped spring between anchors local to two bodies.
+#[derive(Clone, Copy, Debug)]
+pub struct SpringDescriptor {
+    pub anchor1: Point2<f64>,
+    pub anchor2: Point2<f64>,
+    /// The length the spring pulls or pushes the anchors towards.
+    pub length: f64,
+    /// Force per unit of length the spring is stretched or compressed.
+    pub stiffness: f64,
+    /// Force per unit of speed the anchors move apart or together, slowing down oscillation.
+    pub damping: f64,
+}
+
+/// The force generators the engine adds for entities, and removes again along with them.
+#[derive(Clone, Copy, Debug)]
+pub enum ForceField {
+    /// Accelerates dynamic bodies within `radius` of the source body towards it, fading out
+    /// linearly with distance. Negative strength pushes bodies away instead.
+    Radial {
+        source: DefaultBodyHandle,
+        strength: f64,
+        radius: f64,
+    },
+    /// Accelerates dynamic bodies whose center is inside the box around the source body, rotated
+    /// along with it.
+    Wind {
+        source: DefaultBodyHandle,
+        half_extents: Vector2<f64>,
+        acceleration: Vector2<f64>,
+    },
+    Spring {
+        body1: DefaultBodyHandle,
+        body2: DefaultBodyHandle,
+        spring: SpringDescriptor,
+    },
+}
+
+impl ForceField {
+    /// The bodies the force field can't work without.
+    pub fn bodies(&self) -> Vec<DefaultBodyHandle> {
+        match self {
+            ForceField::Radial { source, .. } | ForceField::Wind { source, .. } => vec![*source],
+            ForceField::Spring { body1, body2, .. } => vec![*body1, *body2],
+        }
+    }
+}
+
+impl ForceGenerator<f64, DefaultBodyHandle> for ForceField {
+    fn apply(
+        &mut self,
+        _: &IntegrationParameters<f64>,
+        bodies: &mut dyn BodySet<f64, Handle = DefaultBodyHandle>,
+    ) {
+        match *self {
+            ForceField::Radial {
+                source,
+                strength,
+                radius,
+            } => {
+                let center = match part_state(bodies, source) {
+                    Some((position, _)) => position.translation.vector,
+                    None => return,
+                };
+                accelerate_others(bodies, source, &mut |position| {
+                    let offset = center - position.translation.vector;
+                    let distance = offset.norm();
+                    if distance >= radius || distance == 0.0 {
+                        return None;
+                    }
+                    Some(offset / distance * strength * (1.0 - distance / radius))
+                });
+            }
+            ForceField::Wind {
+                source,
+                half_extents,
+                acceleration,
+            } => {
+                let area = match part_state(bodies, source) {
+                    Some((position, _)) => position,
+                    None => return,
+                };
+                accelerate_others(bodies, source, &mut |position| {
+                    let local =
+                        area.inverse_transform_point(&Point2::from(position.translation.vector));
+                    if local.x.abs() > half_extents.x || local.y.abs() > half_extents.y {
+                        return None;
+                    }
+                    Some(acceleration)
+                });
+            }
+            ForceField::Spring {
+                body1,
+                body2,
+                spring,
+            } => {
+                let (position1, velocity1) = match part_state(bodies, body1) {
+                    Some(state) => state,
+                    None => return,
+                };
+                let (position2, velocity2) = match part_state(bodies, body2) {
+                    Some(state) => state,
+                    None => return,
+                };
+                let offset = position2 * spring.anchor2 - position1 * spring.anchor1;
+                let length = offset.norm();
+                if length == 0.0 {
+                    return;
+                }
+                let direction = offset / length;
+                let separating_speed = (velocity2.linear - velocity1.linear).dot(&direction);
+                let force = direction
+                    * ((length - spring.length) * spring.stiffness
+                        + separating_speed * spring.damping);
+                if let Some(body) = bodies.get_mut(body1) {
+                    body.apply_force_at_local_point(
+                        0,
+                        &force,
+                        &spring.anchor1,
+                        ForceType::Force,
+                        true,
+                    );
+                }
+                if let Some(body) = bodies.get_mut(body2) {
+                    body.apply_force_at_local_point(
+                        0,
+                        &-force,
+                        &spring.anchor2,
+                        ForceType::Force,
+                        true,
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn part_state(
+    bodies: &dyn BodySet<f64, Handle = DefaultBodyHandle>,
+    handle: DefaultBodyHandle,
+) -> Option<(Isometry<f64>, Velocity<f64>)> {
+    let part = bodies.get(handle)?.part(0)?;
+    Some((part.position(), part.velocity()))
+}
+
+/// Applies the acceleration returned for each dynamic body other than the source, by position.
+fn accelerate_others(
+    bodies: &mut dyn BodySet<f64, Handle = DefaultBodyHandle>,
+    source: DefaultBodyHandle,
+    acceleration_at: &mut dyn FnMut(&Isometry<f64>) -> Option<Vector2<f64>>,
+) {
+    bodies.foreach_mut(&mut |handle, body| {
+        if handle == source || body.status() != BodyStatus::Dynamic {
+            return;
+        }
+        let position = match body.part(0) {
+            Some(part) => part.position(),
+            None => return,
+        };
+        if let Some(acceleration) = acceleration_at(&position) {
+            body.apply_force(
+                0,
+                &Force::linear(acceleration),
+                ForceType::AccelerationChange,
+                true,
+            );
+        }
+    });
+}
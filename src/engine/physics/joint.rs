@@ -0,0 +1,113 @@
+use crate::components::Joint;
+use nalgebra::{Point2, Unit, Vector2};
+use nphysics2d::joint::DefaultJointConstraintSet;
+use nphysics2d::joint::FixedConstraint;
+use nphysics2d::joint::MouseConstraint;
+use nphysics2d::joint::PrismaticConstraint;
+use nphysics2d::joint::RevoluteConstraint;
+use nphysics2d::math::Rotation;
+use nphysics2d::object::BodyPartHandle;
+use nphysics2d::object::DefaultBodyHandle;
+
+/// Describes how a joint connects two bodies. Anchors are local to the body they belong to, the
+/// first anchor to the first body. nphysics has no motors or angle limits for these joints, so
+/// only prismatic joints can be limited.
+#[derive(Clone, Copy, Debug)]
+pub enum JointDescriptor {
+    /// Lets the bodies rotate freely around the anchors, like a door on a hinge.
+    Revolute {
+        anchor1: Point2<f64>,
+        anchor2: Point2<f64>,
+    },
+    /// Lets the second body slide along the axis, local to the first body, optionally limited to
+    /// a minimum and maximum offset.
+    Prismatic {
+        anchor1: Point2<f64>,
+        axis: Vector2<f64>,
+        anchor2: Point2<f64>,
+        limits: Option<(f64, f64)>,
+    },
+    /// Keeps the anchors together and the bodies at the same rotation.
+    Fixed {
+        anchor1: Point2<f64>,
+        anchor2: Point2<f64>,
+    },
+    /// Keeps the anchors at most `length` apart, like links of a chain.
+    Rope {
+        anchor1: Point2<f64>,
+        anchor2: Point2<f64>,
+        length: f64,
+    },
+    /// Pulls the second anchor towards the first with at most `max_force`, for dragging bodies
+    /// around with the mouse.
+    Mouse {
+        anchor1: Point2<f64>,
+        anchor2: Point2<f64>,
+        max_force: f64,
+    },
+}
+
+impl JointDescriptor {
+    pub(crate) fn build(
+        &self,
+        constraints: &mut DefaultJointConstraintSet<f64>,
+        body1: DefaultBodyHandle,
+        body2: DefaultBodyHandle,
+    ) -> Joint {
+        let part1 = BodyPartHandle(body1, 0);
+        let part2 = BodyPartHandle(body2, 0);
+        let handle = match *self {
+            JointDescriptor::Revolute { anchor1, anchor2 } => {
+                constraints.insert(RevoluteConstraint::new(part1, part2, anchor1, anchor2))
+            }
+            JointDescriptor::Prismatic {
+                anchor1,
+                axis,
+                anchor2,
+                limits,
+            } => {
+                let mut constraint = PrismaticConstraint::new(
+                    part1,
+                    part2,
+                    anchor1,
+                    Unit::new_normalize(axis),
+                    anchor2,
+                );
+                if let Some((min_offset, max_offset)) = limits {
+                    constraint.enable_min_offset(min_offset);
+                    constraint.enable_max_offset(max_offset);
+                }
+                constraints.insert(constraint)
+            }
+            JointDescriptor::Fixed { anchor1, anchor2 } => {
+                constraints.insert(FixedConstraint::new(
+                    part1,
+                    part2,
+                    anchor1,
+                    Rotation::identity(),
+                    anchor2,
+                    Rotation::identity(),
+                ))
+            }
+            JointDescriptor::Rope {
+                anchor1,
+                anchor2,
+                length,
+            } => {
+                return Joint::Rope {
+                    anchor1,
+                    anchor2,
+                    length,
+                }
+            }
+            JointDescriptor::Mouse {
+                anchor1,
+                anchor2,
+                max_force,
+            } => constraints.insert(MouseConstraint::new(
+                part1, part2, anchor1, anchor2, max_force,
+            )),
+        };
+        Joint::Constraint(handle)
+    }
+}
@@ -1 +1,7 @@
-pub mod resources;
\ No newline at end of file
+pub mod body;
+pub mod collider;
+pub mod events;
+pub mod forces;
+pub mod joint;
+pub mod query;
+pub mod resources;
@@ -0,0 +1,222 @@
+use crate::physics::resources::*;
+use nalgebra::{Point2, Vector2};
+use ncollide2d::bounding_volume::{BoundingVolume, AABB};
+use ncollide2d::pipeline::CollisionGroups;
+use ncollide2d::query;
+use ncollide2d::query::Ray;
+use ncollide2d::shape::Shape;
+use nphysics2d::math::Isometry;
+use nphysics2d::object::{Collider, DefaultBodyHandle};
+use specs::shred::ResourceId;
+use specs::*;
+
+/// Which colliders a query can find.
+#[derive(Clone, Copy, Debug)]
+pub struct QueryFilter {
+    /// Only colliders interacting with these groups are found.
+    pub groups: CollisionGroups,
+    pub include_sensors: bool,
+    /// An entity to ignore, usually the one doing the query.
+    pub exclude: Option<Entity>,
+}
+
+impl Default for QueryFilter {
+    fn default() -> Self {
+        QueryFilter {
+            groups: CollisionGroups::new(),
+            include_sensors: false,
+            exclude: None,
+        }
+    }
+}
+
+impl QueryFilter {
+    pub fn new() -> QueryFilter {
+        QueryFilter::default()
+    }
+
+    pub fn groups(mut self, groups: CollisionGroups) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    pub fn include_sensors(mut self, include_sensors: bool) -> Self {
+        self.include_sensors = include_sensors;
+        self
+    }
+
+    pub fn excluding(mut self, entity: Entity) -> Self {
+        self.exclude = Some(entity);
+        self
+    }
+
+    /// The entity owning the collider, if the filter lets it through.
+    fn accepts(&self, collider: &Collider<f64, DefaultBodyHandle>) -> Option<Entity> {
+        if collider.is_sensor() && !self.include_sensors {
+            return None;
+        }
+        let entity = *collider.user_data()?.downcast_ref::<Entity>()?;
+        if self.exclude == Some(entity) {
+            return None;
+        }
+        Some(entity)
+    }
+}
+
+/// Where a ray or shape cast hit an entity.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QueryHit {
+    pub entity: Entity,
+    /// The point hit, in world space.
+    pub point: Point2<f64>,
+    /// The normal of the surface hit, pointing away from the entity.
+    pub normal: Vector2<f64>,
+    /// How far along the cast the hit is, as a multiple of the cast direction.
+    pub toi: f64,
+}
+
+/// Read-only queries against the colliders of the last physics step, for line of sight, mouse
+/// picking and AI. Use it as system data, or fetch it with `world.system_data::<PhysicsQuery>()`.
+#[derive(SystemData)]
+pub struct PhysicsQuery<'a> {
+    geometrical_world: Read<'a, MyGeometricalWorld>,
+    colliders: Read<'a, MyColliderSet>,
+}
+
+impl PhysicsQuery<'_> {
+    /// The first entity hit by the ray within `max_toi` times its direction.
+    pub fn cast_ray(
+        &self,
+        origin: Point2<f64>,
+        direction: Vector2<f64>,
+        max_toi: f64,
+        filter: &QueryFilter,
+    ) -> Option<QueryHit> {
+        self.cast_ray_all(origin, direction, max_toi, filter)
+            .into_iter()
+            .next()
+    }
+
+    /// Every entity hit by the ray within `max_toi` times its direction, nearest first.
+    pub fn cast_ray_all(
+        &self,
+        origin: Point2<f64>,
+        direction: Vector2<f64>,
+        max_toi: f64,
+        filter: &QueryFilter,
+    ) -> Vec<QueryHit> {
+        let ray = Ray::new(origin, direction);
+        let mut hits: Vec<QueryHit> = self
+            .geometrical_world
+            .0
+            .interferences_with_ray(&self.colliders.0, &ray, &filter.groups)
+            .filter(|(_, _, intersection)| intersection.toi <= max_toi)
+            .filter_map(|(_, collider, intersection)| {
+                Some(QueryHit {
+                    entity: filter.accepts(collider)?,
+                    point: ray.point_at(intersection.toi),
+                    normal: intersection.normal,
+                    toi: intersection.toi,
+                })
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            a.toi
+                .partial_cmp(&b.toi)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        hits
+    }
+
+    /// The first entity the shape would touch moving from `position` by up to `max_toi` times
+    /// `direction`.
+    pub fn cast_shape(
+        &self,
+        shape: &dyn Shape<f64>,
+        position: &Isometry<f64>,
+        direction: &Vector2<f64>,
+        max_toi: f64,
+        filter: &QueryFilter,
+    ) -> Option<QueryHit> {
+        let mut end = *position;
+        end.translation.vector += direction * max_toi;
+        let swept_aabb = shape.aabb(position).merged(&shape.aabb(&end));
+
+        let mut earliest: Option<QueryHit> = None;
+        for (_, collider) in self.geometrical_world.0.interferences_with_aabb(
+            &self.colliders.0,
+            &swept_aabb,
+            &filter.groups,
+        ) {
+            let entity = match filter.accepts(collider) {
+                Some(entity) => entity,
+                None => continue,
+            };
+            let hit = match query::time_of_impact(
+                position,
+                direction,
+                shape,
+                collider.position(),
+                &Vector2::zeros(),
+                collider.shape(),
+                max_toi,
+                0.0,
+            ) {
+                Some(hit) => hit,
+                None => continue,
+            };
+            if earliest.map_or(true, |earliest| hit.toi < earliest.toi) {
+                earliest = Some(QueryHit {
+                    entity,
+                    point: collider.position() * hit.witness2,
+                    normal: collider.position().rotation * hit.normal2.into_inner(),
+                    toi: hit.toi,
+                });
+            }
+        }
+        earliest
+    }
+
+    /// Every entity with a collider containing the point.
+    pub fn entities_at_point(&self, point: &Point2<f64>, filter: &QueryFilter) -> Vec<Entity> {
+        let mut entities = Vec::new();
+        for (_, collider) in self.geometrical_world.0.interferences_with_point(
+            &self.colliders.0,
+            point,
+            &filter.groups,
+        ) {
+            if let Some(entity) = filter.accepts(collider) {
+                if !entities.contains(&entity) {
+                    entities.push(entity);
+                }
+            }
+        }
+        entities
+    }
+
+    /// Every entity with a collider whose bounding box overlaps the box between the two corners.
+    pub fn entities_in_aabb(
+        &self,
+        mins: Point2<f64>,
+        maxs: Point2<f64>,
+        filter: &QueryFilter,
+    ) -> Vec<Entity> {
+        let aabb = AABB::new(mins, maxs);
+        let mut entities = Vec::new();
+        for (_, collider) in self.geometrical_world.0.interferences_with_aabb(
+            &self.colliders.0,
+            &aabb,
+            &filter.groups,
+        ) {
+            if !collider.shape().aabb(collider.position()).intersects(&aabb) {
+                continue;
+            }
+            if let Some(entity) = filter.accepts(collider) {
+                if !entities.contains(&entity) {
+                    entities.push(entity);
+                }
+            }
+        }
+        entities
+    }
+}
@@ -1,10 +1,14 @@
 use nalgebra::Vector2;
 use nphysics2d::force_generator::DefaultForceGeneratorSet;
+use nphysics2d::joint::DefaultJointConstraintHandle;
 use nphysics2d::joint::DefaultJointConstraintSet;
+use nphysics2d::object::DefaultBodyHandle;
 use nphysics2d::object::DefaultBodySet;
 use nphysics2d::object::DefaultColliderSet;
 use nphysics2d::world::DefaultGeometricalWorld;
 use nphysics2d::world::DefaultMechanicalWorld;
+use specs::Entity;
+use std::collections::HashMap;
 use std::fmt::Debug;
 
 pub struct MyMechanicalWorld(pub DefaultMechanicalWorld<f64>);
@@ -71,6 +75,11 @@ impl Debug for MyBodySet {
     }
 }
 
+/// The bodies created along with entities, so their cleanup leaves bodies added to `MyBodySet`
+/// directly alone.
+#[derive(Default, Debug)]
+pub struct EntityBodies(pub HashMap<Entity, DefaultBodyHandle>);
+
 pub struct MyColliderSet(pub DefaultColliderSet<f64>);
 
 impl Default for MyColliderSet {
@@ -102,3 +111,8 @@ impl Debug for MyJointConstraintSet {
         write!(f, "")
     }
 }
+
+/// The joint constraints created for joint entities, so their cleanup leaves constraints added to
+/// `MyJointConstraintSet` directly alone.
+#[derive(Default, Debug)]
+pub struct EntityJoints(pub HashMap<Entity, DefaultJointConstraintHandle>);
@@ -0,0 +1,121 @@
+use crate::resources::*;
+use ggez::event::{Axis, Button, KeyCode, KeyMods, MouseButton};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The input state of a single tick, as stored in a recording.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct InputFrame {
+    pub pressed_keys: Vec<KeyCode>,
+    pub active_mods: u8,
+    pub mouse_position: (f32, f32),
+    /// Recorded since it depends on the camera, which moves with the frame rate. Missing from
+    /// older recordings, which keep the one computed during playback.
+    #[serde(default)]
+    pub mouse_world_position: Option<(f64, f64)>,
+    pub mouse_buttons: Vec<MouseButton>,
+    #[serde(default)]
+    pub gamepad_buttons: Vec<Button>,
+    #[serde(default)]
+    pub gamepad_axes: Vec<(Axis, f32)>,
+}
+
+impl InputFrame {
+    pub fn record(input_context: &InputContext) -> InputFrame {
+        InputFrame {
+            pressed_keys: input_context.pressed_keys.iter().cloned().collect(),
+            active_mods: input_context.active_mods.bits(),
+            mouse_position: input_context.mouse_position,
+            mouse_world_position: Some(input_context.mouse_world_position),
+            mouse_buttons: input_context.mouse_buttons.iter().cloned().collect(),
+            gamepad_buttons: input_context.gamepad_buttons.iter().cloned().collect(),
+            gamepad_axes: input_context
+                .gamepad_axes
+                .iter()
+                .map(|(axis, value)| (*axis, *value))
+                .collect(),
+        }
+    }
+
+    /// Overwrites the live input in the context, leaving the previous tick's state alone so edges
+    /// between ticks are detected the same way as during recording.
+    pub fn apply(&self, input_context: &mut InputContext) {
+        input_context.pressed_keys = self.pressed_keys.iter().cloned().collect();
+        input_context.active_mods = KeyMods::from_bits_truncate(self.active_mods);
+        input_context.mouse_position = self.mouse_position;
+        if let Some(mouse_world_position) = self.mouse_world_position {
+            input_context.mouse_world_position = mouse_world_position;
+        }
+        input_context.mouse_buttons = self.mouse_buttons.iter().cloned().collect();
+        input_context.gamepad_buttons = self.gamepad_buttons.iter().cloned().collect();
+        input_context.gamepad_axes = self.gamepad_axes.iter().cloned().collect();
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct InputRecording {
+    /// The fixed timestep the recording was made with, replays are only deterministic at the same rate.
+    pub step: f64,
+    pub frames: Vec<InputFrame>,
+}
+
+impl InputRecording {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<InputRecording> {
+        let file = fs::File::open(path)?;
+        ron::de::from_reader(file)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let serialized = ron::ser::to_string(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        fs::write(path, serialized)
+    }
+}
+
+/// Where the input of each tick comes from.
+#[derive(Debug)]
+pub enum InputReplay {
+    Live,
+    Recording(InputRecording),
+    Playback {
+        recording: InputRecording,
+        next_frame: usize,
+    },
+}
+
+impl Default for InputReplay {
+    fn default() -> Self {
+        InputReplay::Live
+    }
+}
+
+impl InputReplay {
+    /// Records or replaces the input of the coming tick, depending on the mode. Playback falls
+    /// back to live input once the recording runs out.
+    pub fn process(&mut self, input_context: &mut InputContext) {
+        let finished = match self {
+            InputReplay::Live => false,
+            InputReplay::Recording(recording) => {
+                recording.frames.push(InputFrame::record(input_context));
+                false
+            }
+            InputReplay::Playback {
+                recording,
+                next_frame,
+            } => match recording.frames.get(*next_frame) {
+                Some(frame) => {
+                    frame.apply(input_context);
+                    *next_frame += 1;
+                    false
+                }
+                None => true,
+            },
+        };
+        if finished {
+            *self = InputReplay::Live;
+        }
+    }
+}
@@ -1,23 +1,129 @@
+use ggez::event::Axis;
+use ggez::event::Button;
 use ggez::event::KeyCode;
+use ggez::event::MouseButton;
 use ggez::input::keyboard::KeyMods;
-use ggez::input::mouse::MouseContext;
+use nalgebra::{Point2, Rotation2, Vector2};
+use serde::{Deserialize, Serialize};
+use specs::Entity;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
 #[derive(Default)]
 pub struct DeltaTime(pub f64);
 
+/// Drives the simulation at a fixed rate independent of the frame rate. Frame time is added to
+/// the accumulator and consumed in whole steps, the remainder is used to interpolate rendering.
 #[derive(Debug)]
+pub struct FixedTimestep {
+    pub step: f64,
+    pub accumulator: f64,
+    pub max_ticks_per_frame: u32,
+}
+
+impl FixedTimestep {
+    pub fn new(ticks_per_second: f64) -> FixedTimestep {
+        FixedTimestep {
+            step: 1.0 / ticks_per_second,
+            accumulator: 0.0,
+            max_ticks_per_frame: 8,
+        }
+    }
+
+    /// Adds the frame time to the accumulator and returns how many ticks should be run this frame.
+    /// Time beyond `max_ticks_per_frame` ticks is dropped so a long hitch can't snowball.
+    pub fn advance(&mut self, frame_delta: f64) -> u32 {
+        self.accumulator += frame_delta;
+        let mut ticks = 0;
+        while self.accumulator >= self.step {
+            self.accumulator -= self.step;
+            ticks += 1;
+            if ticks == self.max_ticks_per_frame {
+                self.accumulator = self.accumulator.min(self.step);
+                break;
+            }
+        }
+        ticks
+    }
+
+    /// How far between the previous and the current physics state rendering should be, from 0 to 1.
+    pub fn alpha(&self) -> f64 {
+        (self.accumulator / self.step).min(1.0)
+    }
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        FixedTimestep::new(60.0)
+    }
+}
+
+#[derive(Debug, Default)]
 pub struct InputContext {
     pub pressed_keys: HashSet<KeyCode>,
     pub last_pressed_keys: HashSet<KeyCode>,
     pub active_mods: KeyMods,
-    pub mouse_context: MouseContext,
+    pub mouse_position: (f32, f32),
+    /// Where the mouse points in the world, through the camera as it was at the start of the tick.
+    pub mouse_world_position: (f64, f64),
+    pub mouse_buttons: HashSet<MouseButton>,
+    pub last_mouse_buttons: HashSet<MouseButton>,
+    /// Buttons held on any connected gamepad.
+    pub gamepad_buttons: HashSet<Button>,
+    pub last_gamepad_buttons: HashSet<Button>,
+    /// Axis values from -1 to 1, the latest reported by any connected gamepad.
+    pub gamepad_axes: HashMap<Axis, f32>,
+    pub last_gamepad_axes: HashMap<Axis, f32>,
+}
+
+impl InputContext {
+    /// Remembers the current state as the previous tick's, so presses are detected once per tick.
+    pub fn end_tick(&mut self) {
+        self.last_pressed_keys = self.pressed_keys.clone();
+        self.last_mouse_buttons = self.mouse_buttons.clone();
+        self.last_gamepad_buttons = self.gamepad_buttons.clone();
+        self.last_gamepad_axes = self.gamepad_axes.clone();
+    }
+}
+
+/// What movement does when both directions of an axis are held at once.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum OppositeInputPolicy {
+    /// The directions cancel out, analog values are subtracted from each other.
+    Cancel,
+    /// The direction pressed most recently is used.
+    LastPressedWins,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct MovementSettings {
+    /// Movement shorter than this is ignored, longer movement up to a length of 1 is rescaled to
+    /// start from 0 at the edge of the dead zone. Clamped to at least 0 and less than 1.
+    pub dead_zone: f64,
+    /// Limits movement to a length of 1 so diagonal movement is no faster than cardinal movement.
+    /// Without it, movement longer than 1, such as two held keys, keeps its length.
+    pub normalize: bool,
+    pub opposite_input_policy: OppositeInputPolicy,
+}
+
+impl Default for MovementSettings {
+    fn default() -> Self {
+        MovementSettings {
+            dead_zone: 0.1,
+            normalize: true,
+            opposite_input_policy: OppositeInputPolicy::LastPressedWins,
+        }
+    }
 }
 
 #[derive(Default)]
 pub struct ActionContext {
     pub player_action_map: HashMap<PlayerAction, bool>,
+    /// How strongly each action is applied from 0 to 1, less than 1 for a partially deflected stick.
+    pub player_action_values: HashMap<PlayerAction, f64>,
+    pub movement_settings: MovementSettings,
+    /// Active actions, from the earliest to the most recently pressed.
+    press_order: Vec<PlayerAction>,
 }
 
 impl ActionContext {
@@ -31,11 +137,85 @@ impl ActionContext {
         .iter()
         .cloned()
         .collect();
-        ActionContext { player_action_map }
+        let player_action_values = player_action_map
+            .keys()
+            .map(|action| (action.clone(), 0.0))
+            .collect();
+        ActionContext {
+            player_action_map,
+            player_action_values,
+            movement_settings: MovementSettings::default(),
+            press_order: Vec::new(),
+        }
+    }
+
+    pub fn value(&self, action: &PlayerAction) -> f64 {
+        *self.player_action_values.get(action).unwrap_or(&0.0)
+    }
+
+    pub fn set_action(&mut self, action: PlayerAction, value: f64) {
+        let active = value > 0.0;
+        let was_active = *self.player_action_map.get(&action).unwrap_or(&false);
+        if active && !was_active {
+            self.press_order.push(action.clone());
+        } else if !active {
+            self.press_order.retain(|pressed| *pressed != action);
+        }
+        self.player_action_map.insert(action.clone(), active);
+        self.player_action_values.insert(action, value);
+    }
+
+    /// The direction and strength to move in, with y pointing south like screen coordinates.
+    pub fn movement(&self) -> Vector2<f64> {
+        let movement = Vector2::new(
+            self.resolve_axis(&PlayerAction::MoveEast, &PlayerAction::MoveWest),
+            self.resolve_axis(&PlayerAction::MoveSouth, &PlayerAction::MoveNorth),
+        );
+
+        let settings = &self.movement_settings;
+        let dead_zone = settings.dead_zone.max(0.0).min(1.0 - std::f64::EPSILON);
+        let length = movement.norm();
+        if length <= dead_zone || length == 0.0 {
+            return Vector2::zeros();
+        }
+        let rescaled_length = if length <= 1.0 {
+            (length - dead_zone) / (1.0 - dead_zone)
+        } else if settings.normalize {
+            1.0
+        } else {
+            length
+        };
+        movement * (rescaled_length / length)
+    }
+
+    fn resolve_axis(&self, positive: &PlayerAction, negative: &PlayerAction) -> f64 {
+        let positive_value = self.value(positive);
+        let negative_value = self.value(negative);
+        if positive_value > 0.0 && negative_value > 0.0 {
+            if let OppositeInputPolicy::LastPressedWins =
+                self.movement_settings.opposite_input_policy
+            {
+                return if self.pressed_after(positive, negative) {
+                    positive_value
+                } else {
+                    -negative_value
+                };
+            }
+        }
+        positive_value - negative_value
+    }
+
+    fn pressed_after(&self, action: &PlayerAction, other: &PlayerAction) -> bool {
+        let position = |action| {
+            self.press_order
+                .iter()
+                .position(|pressed| pressed == action)
+        };
+        position(action) > position(other)
     }
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug)]
 pub enum PlayerAction {
     MoveNorth,
     MoveSouth,
@@ -43,6 +223,84 @@ pub enum PlayerAction {
     MoveEast,
 }
 
+/// Decides which part of the world is drawn. The position is the world point shown at the center
+/// of the screen, and a zoom above 1 makes the world appear larger.
+#[derive(Debug)]
+pub struct Camera {
+    pub position: Point2<f64>,
+    pub zoom: f64,
+    pub rotation: f64,
+    /// Size of the screen in pixels.
+    pub viewport: Vector2<f64>,
+    /// Entity whose body the camera follows, such as the player.
+    pub target: Option<Entity>,
+    /// How quickly the camera catches up with its target, where higher is snappier and infinity
+    /// keeps the target exactly centered.
+    pub follow_speed: f64,
+    /// Minimum and maximum world corners the view is kept within, centered on the bounds if they
+    /// are smaller than the view. Rotation is not taken into account.
+    pub bounds: Option<(Point2<f64>, Point2<f64>)>,
+}
+
+impl Camera {
+    /// A camera showing the world the same way it is laid out on the screen, from the origin in
+    /// the top left corner.
+    pub fn new(viewport: Vector2<f64>) -> Camera {
+        Camera {
+            position: Point2::from(viewport / 2.0),
+            zoom: 1.0,
+            rotation: 0.0,
+            viewport,
+            target: None,
+            follow_speed: 5.0,
+            bounds: None,
+        }
+    }
+
+    pub fn world_to_screen(&self, point: &Point2<f64>) -> Point2<f64> {
+        let relative = Rotation2::new(-self.rotation) * (point - self.position) * self.zoom;
+        Point2::from(relative + self.viewport / 2.0)
+    }
+
+    pub fn screen_to_world(&self, point: &Point2<f64>) -> Point2<f64> {
+        let relative = (point.coords - self.viewport / 2.0) / self.zoom;
+        self.position + Rotation2::new(self.rotation) * relative
+    }
+
+    /// Moves the camera towards the target's position, framerate independently.
+    pub fn follow(&mut self, target: &Point2<f64>, delta: f64) {
+        if self.follow_speed.is_infinite() {
+            self.position = *target;
+            return;
+        }
+        let blend = 1.0 - (-self.follow_speed * delta).exp();
+        self.position += (target - self.position) * blend;
+    }
+
+    pub fn clamp_to_bounds(&mut self) {
+        let (min, max) = match self.bounds {
+            Some(bounds) => bounds,
+            None => return,
+        };
+        let half_view = self.viewport / (2.0 * self.zoom);
+        for axis in 0..2 {
+            self.position[axis] = if max[axis] - min[axis] < half_view[axis] * 2.0 {
+                (min[axis] + max[axis]) / 2.0
+            } else {
+                self.position[axis]
+                    .max(min[axis] + half_view[axis])
+                    .min(max[axis] - half_view[axis])
+            };
+        }
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera::new(Vector2::zeros())
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct GameOptions {
     pub draw_colliders: bool,
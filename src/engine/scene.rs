@@ -0,0 +1,569 @@
+use crate::components::*;
+use crate::error::EngineError;
+use crate::hot_reload::LoadedFiles;
+use crate::physics::body::BodyDescriptor;
+use crate::physics::collider::ColliderDescriptor;
+use crate::physics::resources::*;
+use crate::GameState;
+use ggez::graphics::FilterMode;
+use ggez::Context;
+use nalgebra::{Point2, Vector2};
+use ncollide2d::pipeline::CollisionGroups;
+use ncollide2d::shape::{Ball, Cuboid, Polyline, ShapeHandle};
+use nphysics2d::material::BasicMaterial;
+use nphysics2d::object::{Collider, DefaultBodyHandle};
+use serde::{Deserialize, Serialize};
+use specs::*;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A set of entities that can be instantiated into a game state and saved back out.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Scene {
+    pub entities: Vec<EntityDescription>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(from = "EntityDescriptionFields")]
+pub struct EntityDescription {
+    pub position: (f64, f64),
+    #[serde(default)]
+    pub rotation: f64,
+    #[serde(default)]
+    pub body: BodyDescriptor,
+    #[serde(default)]
+    pub sprite: Option<SpriteDescription>,
+    #[serde(default)]
+    pub colliders: Vec<ColliderDescription>,
+    #[serde(default)]
+    pub player: Option<Player>,
+}
+
+/// The fields entity descriptions are read from, including the single `collider` scenes were saved
+/// with before entities could have several.
+#[derive(Deserialize)]
+struct EntityDescriptionFields {
+    position: (f64, f64),
+    #[serde(default)]
+    rotation: f64,
+    #[serde(default)]
+    body: BodyDescriptor,
+    #[serde(default)]
+    sprite: Option<SpriteDescription>,
+    #[serde(default)]
+    collider: Option<ColliderDescription>,
+    #[serde(default)]
+    colliders: Vec<ColliderDescription>,
+    #[serde(default)]
+    player: Option<Player>,
+}
+
+impl From<EntityDescriptionFields> for EntityDescription {
+    fn from(fields: EntityDescriptionFields) -> Self {
+        let mut colliders = fields.colliders;
+        if let Some(collider) = fields.collider {
+            colliders.insert(0, collider);
+        }
+        EntityDescription {
+            position: fields.position,
+            rotation: fields.rotation,
+            body: fields.body,
+            sprite: fields.sprite,
+            colliders,
+            player: fields.player,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpriteDescription {
+    pub path: String,
+    /// Frame of the atlas next to the image to show instead of the whole image.
+    #[serde(default)]
+    pub frame: Option<String>,
+    #[serde(default)]
+    pub filter: Filter,
+    #[serde(default)]
+    pub order: RenderOrder,
+}
+
+/// Serializable mirror of ggez's `FilterMode`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Filter {
+    Linear,
+    Nearest,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter::Linear
+    }
+}
+
+impl From<Filter> for FilterMode {
+    fn from(filter: Filter) -> Self {
+        match filter {
+            Filter::Linear => FilterMode::Linear,
+            Filter::Nearest => FilterMode::Nearest,
+        }
+    }
+}
+
+impl From<FilterMode> for Filter {
+    fn from(filter_mode: FilterMode) -> Self {
+        match filter_mode {
+            FilterMode::Linear => Filter::Linear,
+            FilterMode::Nearest => Filter::Nearest,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ColliderDescription {
+    pub shape: ShapeDescription,
+    #[serde(default)]
+    pub material: MaterialDescription,
+    #[serde(default)]
+    pub density: f64,
+    /// Position relative to the body.
+    #[serde(default)]
+    pub offset: (f64, f64),
+    #[serde(default)]
+    pub rotation: f64,
+    #[serde(default)]
+    pub groups: CollisionGroupsDescription,
+    /// Sensors report overlapping entities instead of colliding with them.
+    #[serde(default)]
+    pub sensor: bool,
+}
+
+impl ColliderDescription {
+    pub fn to_descriptor(&self) -> ColliderDescriptor {
+        let mut descriptor = ColliderDescriptor::new(self.shape.to_shape_handle())
+            .material(self.material.restitution, self.material.friction)
+            .density(self.density)
+            .offset(self.offset.0, self.offset.1, self.rotation)
+            .sensor(self.sensor);
+        descriptor.collision_groups = self.groups.to_collision_groups();
+        descriptor
+    }
+
+    /// Describes the collider, or returns `None` for colliders scenes can't contain.
+    pub fn from_collider(
+        collider: &Collider<f64, DefaultBodyHandle>,
+    ) -> Option<ColliderDescription> {
+        let shape = ShapeDescription::from_shape_handle(collider.shape_handle())?;
+        let material = collider
+            .material()
+            .downcast_ref::<BasicMaterial<f64>>()
+            .map(|material| MaterialDescription {
+                restitution: material.restitution,
+                friction: material.friction,
+            })
+            .unwrap_or_default();
+        let offset = collider.position_wrt_body();
+        Some(ColliderDescription {
+            shape,
+            material,
+            density: collider.density(),
+            offset: (offset.translation.vector.x, offset.translation.vector.y),
+            rotation: offset.rotation.angle(),
+            groups: CollisionGroupsDescription::from_collision_groups(collider.collision_groups()),
+            sensor: collider.is_sensor(),
+        })
+    }
+}
+
+/// Collision groups by id, from 0 to 29. Leaving membership or whitelist out means every group.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CollisionGroupsDescription {
+    #[serde(default)]
+    pub membership: Option<Vec<usize>>,
+    #[serde(default)]
+    pub whitelist: Option<Vec<usize>>,
+    #[serde(default)]
+    pub blacklist: Vec<usize>,
+}
+
+impl CollisionGroupsDescription {
+    pub fn to_collision_groups(&self) -> CollisionGroups {
+        let mut collision_groups = CollisionGroups::new();
+        if let Some(membership) = &self.membership {
+            collision_groups.set_membership(membership);
+        }
+        if let Some(whitelist) = &self.whitelist {
+            collision_groups.set_whitelist(whitelist);
+        }
+        collision_groups.set_blacklist(&self.blacklist);
+        collision_groups
+    }
+
+    pub fn from_collision_groups(collision_groups: &CollisionGroups) -> CollisionGroupsDescription {
+        let groups = |filter: &dyn Fn(usize) -> bool| -> Vec<usize> {
+            (0..=CollisionGroups::max_group_id())
+                .filter(|group| filter(*group))
+                .collect()
+        };
+        let unless_all = |groups: Vec<usize>| {
+            if groups.len() == CollisionGroups::max_group_id() + 1 {
+                None
+            } else {
+                Some(groups)
+            }
+        };
+        CollisionGroupsDescription {
+            membership: unless_all(groups(&|group| collision_groups.is_member_of(group))),
+            whitelist: unless_all(groups(&|group| {
+                collision_groups.is_group_whitelisted(group)
+            })),
+            blacklist: groups(&|group| collision_groups.is_group_blacklisted(group)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ShapeDescription {
+    Cuboid { half_extents: (f64, f64) },
+    Ball { radius: f64 },
+    Polyline { points: Vec<(f64, f64)> },
+}
+
+impl ShapeDescription {
+    pub fn to_shape_handle(&self) -> ShapeHandle<f64> {
+        match self {
+            ShapeDescription::Cuboid { half_extents } => {
+                ShapeHandle::new(Cuboid::new(Vector2::new(half_extents.0, half_extents.1)))
+            }
+            ShapeDescription::Ball { radius } => ShapeHandle::new(Ball::new(*radius)),
+            ShapeDescription::Polyline { points } => ShapeHandle::new(Polyline::new(
+                points
+                    .iter()
+                    .map(|point| Point2::new(point.0, point.1))
+                    .collect(),
+                None,
+            )),
+        }
+    }
+
+    /// Describes the shape, or returns `None` for shapes scenes can't contain.
+    pub fn from_shape_handle(shape: &ShapeHandle<f64>) -> Option<ShapeDescription> {
+        if let Some(cuboid) = shape.as_shape::<Cuboid<f64>>() {
+            let half_extents = cuboid.half_extents();
+            Some(ShapeDescription::Cuboid {
+                half_extents: (half_extents.x, half_extents.y),
+            })
+        } else if let Some(ball) = shape.as_shape::<Ball<f64>>() {
+            Some(ShapeDescription::Ball {
+                radius: ball.radius(),
+            })
+        } else if let Some(polyline) = shape.as_shape::<Polyline<f64>>() {
+            Some(ShapeDescription::Polyline {
+                points: polyline
+                    .points()
+                    .iter()
+                    .map(|point| (point.x, point.y))
+                    .collect(),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct MaterialDescription {
+    pub restitution: f64,
+    pub friction: f64,
+}
+
+impl Default for MaterialDescription {
+    fn default() -> Self {
+        MaterialDescription {
+            restitution: 1.0,
+            friction: 0.2,
+        }
+    }
+}
+
+impl Scene {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Scene> {
+        let file = fs::File::open(path)?;
+        ron::de::from_reader(file)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let serialized = ron::ser::to_string_pretty(self, Default::default())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        fs::write(path, serialized)
+    }
+}
+
+/// Instantiates the scene in the file and remembers it, so it can be hot reloaded.
+pub fn load_scene<P: AsRef<Path>>(
+    game_state: &mut GameState,
+    path: P,
+) -> Result<Vec<Entity>, EngineError> {
+    let scene = Scene::load(&path)?;
+    let entities = instantiate_scene(game_state, &scene);
+    game_state
+        .ecs
+        .world
+        .write_resource::<LoadedFiles>()
+        .scenes
+        .insert(fs::canonicalize(path)?, entities.clone());
+    Ok(entities)
+}
+
+pub fn save_scene<P: AsRef<Path>>(game_state: &GameState, path: P) -> Result<(), EngineError> {
+    capture_scene(game_state).save(path)?;
+    Ok(())
+}
+
+/// Creates the entities of the scene in the game state. Sprites are skipped in headless game
+/// states since there is no context to load images with.
+pub fn instantiate_scene(game_state: &mut GameState, scene: &Scene) -> Vec<Entity> {
+    let world = &mut game_state.ecs.world;
+    let mut context = game_state.context.as_mut();
+    scene
+        .entities
+        .iter()
+        .map(|description| {
+            instantiate_entity(world, context.as_mut().map(|c| &mut **c), description)
+        })
+        .collect()
+}
+
+/// Applies a changed scene to the entities previously created from it. Existing entities keep
+/// their bodies, so position and velocity carry over, while sprites, colliders and player data are
+/// replaced. Entities added to the scene are created and entities removed from it are deleted.
+pub(crate) fn reapply_scene(
+    world: &mut World,
+    mut context: Option<&mut Context>,
+    scene: &Scene,
+    entities: &[Entity],
+) -> Vec<Entity> {
+    let mut reapplied = Vec::new();
+    for (index, description) in scene.entities.iter().enumerate() {
+        let context = context.as_mut().map(|c| &mut **c);
+        match entities.get(index) {
+            Some(entity) if world.is_alive(*entity) => {
+                // Kept even if partly updated, so the entities stay in step with the scene's and
+                // the next reload updates it again
+                if let Err(e) = update_entity(world, context, *entity, description) {
+                    log::warn!("Failed updating entity from scene: {}", e);
+                }
+                reapplied.push(*entity);
+            }
+            _ => reapplied.push(instantiate_entity(world, context, description)),
+        }
+    }
+    for entity in entities.iter().skip(scene.entities.len()) {
+        if let Err(e) = crate::destroy_entity_in(world, *entity) {
+            log::warn!("Failed removing entity deleted from scene: {}", e);
+        }
+    }
+    reapplied
+}
+
+fn load_sprite(
+    world: &World,
+    context: Option<&mut Context>,
+    description: &SpriteDescription,
+) -> Option<Sprite> {
+    let context = context?;
+    let frame = description.frame.as_ref().map(|frame| frame.as_str());
+    let mut sprite = match crate::load_sprite_in(world, context, &description.path, frame) {
+        Ok(sprite) => sprite,
+        Err(e) => {
+            log::warn!("Skipping sprite: {}", e);
+            return None;
+        }
+    };
+    sprite.image.set_filter(description.filter.into());
+    Some(sprite)
+}
+
+/// The sprite's render order, if it isn't drawn at the default depth.
+fn custom_render_order(description: &EntityDescription) -> Option<RenderOrder> {
+    description
+        .sprite
+        .as_ref()
+        .map(|sprite| sprite.order)
+        .filter(|order| *order != RenderOrder::default())
+}
+
+fn instantiate_entity(
+    world: &mut World,
+    context: Option<&mut Context>,
+    description: &EntityDescription,
+) -> Entity {
+    let sprite = description
+        .sprite
+        .as_ref()
+        .and_then(|sprite| load_sprite(world, context, sprite));
+
+    let mut builder = crate::create_entity_in(
+        world,
+        description.position.0,
+        description.position.1,
+        description.rotation,
+        &description.body,
+    );
+    if let Some(sprite) = sprite {
+        builder = builder.with(sprite);
+    }
+    if let Some(render_order) = custom_render_order(description) {
+        builder = builder.with(render_order);
+    }
+    if let Some(player) = &description.player {
+        builder = builder.with(player.clone());
+    }
+    let entity = builder.build();
+
+    for collider in &description.colliders {
+        if let Err(e) = crate::add_collider_in(world, entity, &collider.to_descriptor()) {
+            log::warn!("Failed adding collider from scene: {}", e);
+        }
+    }
+    entity
+}
+
+fn update_entity(
+    world: &mut World,
+    context: Option<&mut Context>,
+    entity: Entity,
+    description: &EntityDescription,
+) -> Result<(), EngineError> {
+    let has_context = context.is_some();
+    match description
+        .sprite
+        .as_ref()
+        .and_then(|sprite| load_sprite(world, context, sprite))
+    {
+        Some(sprite) => {
+            world.write_storage::<Sprite>().insert(entity, sprite)?;
+        }
+        // Without a context the sprite couldn't have been loaded in the first place
+        None if has_context => {
+            world.write_storage::<Sprite>().remove(entity);
+        }
+        None => {}
+    }
+    match custom_render_order(description) {
+        Some(render_order) => {
+            world
+                .write_storage::<RenderOrder>()
+                .insert(entity, render_order)?;
+        }
+        None => {
+            world.write_storage::<RenderOrder>().remove(entity);
+        }
+    }
+
+    if let Some(transform) = world.read_storage::<TransformComponent>().get(entity) {
+        if let Some(body) = world
+            .write_resource::<MyBodySet>()
+            .0
+            .rigid_body_mut(transform.0)
+        {
+            description.body.apply(body);
+        }
+    }
+    if description.body.has_custom_gravity() {
+        world
+            .write_storage::<GravityScale>()
+            .insert(entity, GravityScale(description.body.gravity_scale))?;
+    } else {
+        world.write_storage::<GravityScale>().remove(entity);
+    }
+    world
+        .write_storage::<BodyMass>()
+        .insert(entity, BodyMass(description.body.mass))?;
+
+    match &description.player {
+        Some(player) => {
+            world
+                .write_storage::<Player>()
+                .insert(entity, player.clone())?;
+        }
+        None => {
+            world.write_storage::<Player>().remove(entity);
+        }
+    }
+
+    if let Some(collider_component) = world.write_storage::<ColliderComponent>().remove(entity) {
+        let mut colliders = world.write_resource::<MyColliderSet>();
+        for collider_handle in collider_component.0 {
+            colliders.0.remove(collider_handle);
+        }
+    }
+    for collider in &description.colliders {
+        if let Err(e) = crate::add_collider_in(world, entity, &collider.to_descriptor()) {
+            log::warn!("Failed adding collider from scene: {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Describes every entity with a transform in the game state.
+pub fn capture_scene(game_state: &GameState) -> Scene {
+    let world = &game_state.ecs.world;
+    let bodies = world.read_resource::<MyBodySet>();
+    let colliders = world.read_resource::<MyColliderSet>();
+    let transforms = world.read_storage::<TransformComponent>();
+    let sprites = world.read_storage::<Sprite>();
+    let collider_components = world.read_storage::<ColliderComponent>();
+    let players = world.read_storage::<Player>();
+    let gravity_scales = world.read_storage::<GravityScale>();
+    let masses = world.read_storage::<BodyMass>();
+    let render_orders = world.read_storage::<RenderOrder>();
+
+    let mut entities = Vec::new();
+    for (transform, sprite, collider_component, player, gravity_scale, mass, render_order) in (
+        &transforms,
+        sprites.maybe(),
+        collider_components.maybe(),
+        players.maybe(),
+        gravity_scales.maybe(),
+        masses.maybe(),
+        render_orders.maybe(),
+    )
+        .join()
+    {
+        let body = match bodies.0.rigid_body(transform.0) {
+            Some(body) => body,
+            None => continue,
+        };
+        let position = body.position();
+        let collider_descriptions = collider_component
+            .map(|collider_component| {
+                collider_component
+                    .0
+                    .iter()
+                    .filter_map(|collider_handle| colliders.0.get(*collider_handle))
+                    .filter_map(ColliderDescription::from_collider)
+                    .collect()
+            })
+            .unwrap_or_default();
+        entities.push(EntityDescription {
+            position: (position.translation.vector.x, position.translation.vector.y),
+            rotation: position.rotation.angle(),
+            body: BodyDescriptor::from_body(
+                body,
+                mass.map(|mass| mass.0),
+                gravity_scale.map(|gravity_scale| gravity_scale.0),
+            ),
+            sprite: sprite.map(|sprite| SpriteDescription {
+                path: sprite.path.clone(),
+                frame: sprite.frame.clone(),
+                filter: sprite.image.filter().into(),
+                order: render_order.cloned().unwrap_or_default(),
+            }),
+            colliders: collider_descriptions,
+            player: player.cloned(),
+        });
+    }
+    Scene { entities }
+}
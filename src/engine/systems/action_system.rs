@@ -11,28 +11,55 @@ impl<'a> System<'a> for ActionSystem {
     type SystemData = (
         ReadStorage<'a, Player>,
         ReadStorage<'a, TransformComponent>,
+        WriteStorage<'a, CharacterController>,
+        ReadStorage<'a, MovementClips>,
+        WriteStorage<'a, Animation>,
         Read<'a, ActionContext>,
         Write<'a, MyBodySet>,
     );
-    fn run(&mut self, (player, transform, action_context, mut bodies): Self::SystemData) {
-        for (player, body_handle) in (&player, &transform).join() {
-            let mut force = nalgebra::Vector2::new(0f64, 0f64);
-            if action_context.player_action_map[&PlayerAction::MoveNorth] {
-                force.y = -player.movement_speed;
-            } else if action_context.player_action_map[&PlayerAction::MoveSouth] {
-                force.y = player.movement_speed;
+    fn run(
+        &mut self,
+        (
+            player,
+            transform,
+            mut controllers,
+            movement_clips,
+            mut animations,
+            action_context,
+            mut bodies,
+        ): Self::SystemData,
+    ) {
+        for (player, body_handle, controller, clips, animation) in (
+            &player,
+            &transform,
+            (&mut controllers).maybe(),
+            movement_clips.maybe(),
+            (&mut animations).maybe(),
+        )
+            .join()
+        {
+            let force = action_context.movement() * player.movement_speed;
+            if let (Some(clips), Some(animation)) = (clips, animation) {
+                // One-shot clips started by gameplay, such as an attack, are played to the end
+                if !animation.is_playing_once() {
+                    if let Some(clip) = clips.clip_for(&force) {
+                        animation.play(clip);
+                    }
+                }
             }
-            if action_context.player_action_map[&PlayerAction::MoveEast] {
-                force.x = player.movement_speed;
-            } else if action_context.player_action_map[&PlayerAction::MoveWest] {
-                force.x = -player.movement_speed;
+            // Players with a character controller are moved by it instead of by forces
+            if let Some(controller) = controller {
+                controller.desired_velocity = force;
+                continue;
             }
-            if !force.is_empty() {
-                let body = bodies
-                    .0
-                    .rigid_body_mut(body_handle.0)
-                    .expect("Body no longer existing for handle!")
-                    as &mut RigidBody<f64>;
+            if force != nalgebra::Vector2::zeros() {
+                let body = match bodies.0.rigid_body_mut(body_handle.0) {
+                    Some(body) => body as &mut RigidBody<f64>,
+                    None => {
+                        log::warn!("Skipping movement of stale body {:?}", body_handle.0);
+                        continue;
+                    }
+                };
 
                 body.apply_force(
                     0,
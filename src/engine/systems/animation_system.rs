@@ -0,0 +1,62 @@
+use crate::animation::*;
+use crate::assets::*;
+use crate::components::*;
+use crate::resources::*;
+use specs::shrev::EventChannel;
+use specs::*;
+
+/// Advances animations and shows their current frame on the entity's sprite. Frames come from
+/// atlases loaded before the ticks of each frame, since there is no context to load them with
+/// during a tick.
+pub struct AnimationSystem;
+
+impl<'a> System<'a> for AnimationSystem {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, DeltaTime>,
+        Read<'a, AssetCache>,
+        WriteStorage<'a, Animation>,
+        WriteStorage<'a, Sprite>,
+        Write<'a, EventChannel<AnimationEvent>>,
+    );
+    fn run(
+        &mut self,
+        (
+            entities,
+            delta,
+            asset_cache,
+            mut animations,
+            mut sprites,
+            mut animation_events,
+        ): Self::SystemData,
+    ) {
+        for (entity, animation, sprite) in
+            (&entities, &mut animations, (&mut sprites).maybe()).join()
+        {
+            if animation.advance(delta.0) {
+                animation_events.single_write(AnimationEvent::ClipFinished {
+                    entity,
+                    clip: animation.current_clip().to_string(),
+                });
+            }
+
+            let (sprite, frame) = match (sprite, animation.current_frame()) {
+                (Some(sprite), Some(frame)) => (sprite, frame),
+                _ => continue,
+            };
+            if sprite.frame.as_ref().map(|shown| shown.as_str()) == Some(frame) {
+                continue;
+            }
+            // Remembered even if missing, so it's only reported once and picked up on hot reload
+            sprite.frame = Some(frame.to_string());
+            match asset_cache.loaded_frame(&sprite.path, frame) {
+                Some(src) => sprite.src = src,
+                None => log::warn!(
+                    "Frame {} missing from atlas of {}, keeping the previous frame",
+                    frame,
+                    sprite.path
+                ),
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::components::*;
+use crate::physics::resources::*;
+use crate::resources::*;
+use crate::systems::draw_system::interpolate;
+use nalgebra::Point2;
+use specs::*;
+
+/// Moves the camera towards its target entity and keeps it within its bounds. Runs once per drawn
+/// frame and follows the interpolated position sprites are drawn at, so the target doesn't jitter
+/// when the frame rate and tick rate differ.
+pub struct CameraSystem {
+    /// Time since the previous frame, in seconds.
+    frame_delta: f64,
+}
+
+impl CameraSystem {
+    pub fn new(frame_delta: f64) -> CameraSystem {
+        CameraSystem { frame_delta }
+    }
+}
+
+impl<'a> System<'a> for CameraSystem {
+    type SystemData = (
+        Read<'a, FixedTimestep>,
+        ReadStorage<'a, TransformComponent>,
+        ReadStorage<'a, PreviousTransform>,
+        Read<'a, MyBodySet>,
+        Write<'a, Camera>,
+    );
+    fn run(
+        &mut self,
+        (timestep, transforms, previous_transforms, bodies, mut camera): Self::SystemData,
+    ) {
+        let target = camera.target.and_then(|entity| {
+            let body = bodies.0.rigid_body(transforms.get(entity)?.0)?;
+            let position = interpolate(
+                previous_transforms.get(entity),
+                body.position(),
+                timestep.alpha(),
+            );
+            Some(Point2::from(position.translation.vector))
+        });
+        if let Some(target) = target {
+            camera.follow(&target, self.frame_delta);
+        }
+        camera.clamp_to_bounds();
+    }
+}
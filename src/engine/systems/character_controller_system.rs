@@ -0,0 +1,145 @@
+use crate::components::*;
+use crate::physics::resources::*;
+use crate::resources::*;
+use nalgebra::{Translation2, Vector2};
+use ncollide2d::bounding_volume::BoundingVolume;
+use ncollide2d::query;
+use ncollide2d::query::TOIStatus;
+use nphysics2d::math::Isometry;
+use nphysics2d::object::*;
+use specs::*;
+
+/// Motion shorter than this isn't worth sweeping for.
+const MIN_MOTION: f64 = 1.0e-6;
+
+type DefaultCollider = Collider<f64, DefaultBodyHandle>;
+
+pub struct CharacterControllerSystem;
+
+impl<'a> System<'a> for CharacterControllerSystem {
+    type SystemData = (
+        Read<'a, DeltaTime>,
+        WriteStorage<'a, CharacterController>,
+        ReadStorage<'a, TransformComponent>,
+        ReadStorage<'a, ColliderComponent>,
+        Read<'a, MyGeometricalWorld>,
+        Read<'a, MyColliderSet>,
+        Write<'a, MyBodySet>,
+    );
+    fn run(
+        &mut self,
+        (
+            delta,
+            mut controllers,
+            transforms,
+            collider_components,
+            geometrical_world,
+            colliders,
+            mut bodies,
+        ): Self::SystemData,
+    ) {
+        for (controller, transform, collider_component) in
+            (&mut controllers, &transforms, &collider_components).join()
+        {
+            let body = match bodies.0.rigid_body_mut(transform.0) {
+                Some(body) => body,
+                None => continue,
+            };
+            let solid_colliders: Vec<&DefaultCollider> = collider_component
+                .0
+                .iter()
+                .filter_map(|collider_handle| colliders.0.get(*collider_handle))
+                .filter(|collider| !collider.is_sensor())
+                .collect();
+
+            let mut position = *body.position();
+            let mut motion = controller.desired_velocity * delta.0;
+            controller.collided = false;
+            for _ in 0..=controller.max_slides {
+                if motion.norm() < MIN_MOTION {
+                    break;
+                }
+                match sweep(
+                    &geometrical_world,
+                    &colliders,
+                    transform.0,
+                    &solid_colliders,
+                    &position,
+                    &motion,
+                    controller.skin_width,
+                ) {
+                    Some((toi, normal)) => {
+                        controller.collided = true;
+                        position.translation.vector += motion * toi;
+                        // Whatever is left of the motion continues along the surface
+                        let remaining = motion * (1.0 - toi);
+                        motion = remaining - normal * remaining.dot(&normal);
+                    }
+                    None => {
+                        position.translation.vector += motion;
+                        break;
+                    }
+                }
+            }
+            body.set_position(position);
+        }
+    }
+}
+
+/// Finds the earliest time, as a fraction of the motion, any of the colliders would come within
+/// `skin_width` of another solid collider, along with the normal of the surface it would hit.
+fn sweep(
+    geometrical_world: &MyGeometricalWorld,
+    colliders: &MyColliderSet,
+    body_handle: DefaultBodyHandle,
+    solid_colliders: &[&DefaultCollider],
+    body_position: &Isometry<f64>,
+    motion: &Vector2<f64>,
+    skin_width: f64,
+) -> Option<(f64, Vector2<f64>)> {
+    let mut earliest: Option<(f64, Vector2<f64>)> = None;
+    for collider in solid_colliders {
+        let start = body_position * collider.position_wrt_body();
+        let end = Translation2::from(*motion) * start;
+        let swept_aabb = collider
+            .shape()
+            .aabb(&start)
+            .merged(&collider.shape().aabb(&end))
+            .loosened(skin_width);
+
+        for (_, obstacle) in geometrical_world.0.interferences_with_aabb(
+            &colliders.0,
+            &swept_aabb,
+            collider.collision_groups(),
+        ) {
+            if obstacle.body() == body_handle || obstacle.is_sensor() {
+                continue;
+            }
+            let hit = match query::time_of_impact(
+                &start,
+                motion,
+                collider.shape(),
+                obstacle.position(),
+                &Vector2::zeros(),
+                obstacle.shape(),
+                1.0,
+                skin_width,
+            ) {
+                Some(hit) => hit,
+                None => continue,
+            };
+            // Starting out overlapping shouldn't keep the character from moving out again
+            if let TOIStatus::Penetrating = hit.status {
+                continue;
+            }
+            let normal = obstacle.position().rotation * hit.normal2.into_inner();
+            if motion.dot(&normal) >= 0.0 {
+                continue;
+            }
+            if earliest.map_or(true, |(toi, _)| hit.toi < toi) {
+                earliest = Some((hit.toi, normal));
+            }
+        }
+    }
+    earliest
+}
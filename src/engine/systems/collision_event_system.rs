@@ -0,0 +1,59 @@
+use crate::components::*;
+use crate::physics::events::*;
+use crate::physics::resources::*;
+use ncollide2d::pipeline::narrow_phase::ContactEvent;
+use ncollide2d::query::Proximity;
+use nphysics2d::object::DefaultColliderHandle;
+use specs::shrev::EventChannel;
+use specs::*;
+use std::collections::HashMap;
+
+/// Translates the contact and proximity events of the last physics step from collider handles to
+/// the entities owning them. Events involving colliders without an entity are dropped.
+pub struct CollisionEventSystem;
+
+impl<'a> System<'a> for CollisionEventSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, ColliderComponent>,
+        Read<'a, MyGeometricalWorld>,
+        Write<'a, EventChannel<CollisionEvent>>,
+    );
+    fn run(
+        &mut self,
+        (entities, colliders, geometrical_world, mut collision_events): Self::SystemData,
+    ) {
+        let owners: HashMap<DefaultColliderHandle, Entity> = (&entities, &colliders)
+            .join()
+            .flat_map(|(entity, collider)| collider.0.iter().map(move |handle| (*handle, entity)))
+            .collect();
+        let owner_pair =
+            |collider1, collider2| Some((*owners.get(&collider1)?, *owners.get(&collider2)?));
+
+        for event in geometrical_world.0.contact_events() {
+            let collision_event = match *event {
+                ContactEvent::Started(collider1, collider2) => owner_pair(collider1, collider2)
+                    .map(|(entity1, entity2)| CollisionEvent::ContactStarted(entity1, entity2)),
+                ContactEvent::Stopped(collider1, collider2) => owner_pair(collider1, collider2)
+                    .map(|(entity1, entity2)| CollisionEvent::ContactStopped(entity1, entity2)),
+            };
+            if let Some(collision_event) = collision_event {
+                collision_events.single_write(collision_event);
+            }
+        }
+
+        for event in geometrical_world.0.proximity_events() {
+            let (entity1, entity2) = match owner_pair(event.collider1, event.collider2) {
+                Some(pair) => pair,
+                None => continue,
+            };
+            let was_intersecting = event.prev_status == Proximity::Intersecting;
+            let is_intersecting = event.new_status == Proximity::Intersecting;
+            if is_intersecting && !was_intersecting {
+                collision_events.single_write(CollisionEvent::ProximityEntered(entity1, entity2));
+            } else if was_intersecting && !is_intersecting {
+                collision_events.single_write(CollisionEvent::ProximityExited(entity1, entity2));
+            }
+        }
+    }
+}
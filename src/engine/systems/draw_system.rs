@@ -3,8 +3,12 @@ use crate::components::*;
 use crate::physics::resources::*;
 use crate::resources::*;
 use ggez::*;
+use graphics::spritebatch::SpriteBatch;
 use graphics::*;
+use nphysics2d::math::Isometry;
 use specs::*;
+use std::cmp::Ordering;
+use std::ops::Range;
 
 pub struct DrawSystem<'a> {
     context: &'a mut Context,
@@ -19,95 +23,241 @@ impl<'a> DrawSystem<'a> {
 impl<'a> System<'a> for DrawSystem<'a> {
     type SystemData = (
         Read<'a, DeltaTime>,
+        Read<'a, FixedTimestep>,
         ReadStorage<'a, TransformComponent>,
+        ReadStorage<'a, PreviousTransform>,
         ReadStorage<'a, Sprite>,
+        ReadStorage<'a, RenderOrder>,
         Read<'a, GameOptions>,
         Read<'a, DebugInfo>,
         Read<'a, MyBodySet>,
         Read<'a, MyColliderSet>,
         ReadStorage<'a, ColliderComponent>,
         Read<'a, MyGeometricalWorld>,
+        Read<'a, Camera>,
     );
 
     fn run(
         &mut self,
         (
             delta,
+            timestep,
             transform_storage,
+            previous_transform_storage,
             sprite_storage,
+            render_order_storage,
             options,
             debug_info,
             bodies,
             colliders,
             collider_storage,
             geometrical_world,
+            camera,
         ): Self::SystemData,
     ) {
-        for (transform, sprite) in (&transform_storage, &sprite_storage).join() {
-            let transform = (*bodies)
-                .0
-                .rigid_body(transform.0)
-                .expect("Body handle unusable for drawing!")
-                .position();
-            graphics::draw(
-                self.context,
-                &sprite.image,
-                DrawParam {
-                    dest: na::Point2::new(
-                        transform.translation.x as f32,
-                        transform.translation.y as f32,
+        let alpha = timestep.alpha();
+        let mut sprites = Vec::new();
+        for (transform, sprite, render_order, previous_transform) in (
+            &transform_storage,
+            &sprite_storage,
+            render_order_storage.maybe(),
+            previous_transform_storage.maybe(),
+        )
+            .join()
+        {
+            let transform = match (*bodies).0.rigid_body(transform.0) {
+                Some(body) => body.position(),
+                None => {
+                    log::warn!("Skipping sprite of stale body {:?}", transform.0);
+                    continue;
+                }
+            };
+            let transform = interpolate(previous_transform, transform, alpha);
+            let render_order = render_order.cloned().unwrap_or_default();
+            sprites.push((render_order, transform, sprite));
+        }
+        // The sort is stable, so sprites drawn at the same depth keep their storage order
+        sprites.sort_by(|(order1, transform1, _), (order2, transform2, _)| {
+            draw_order(order1, transform1, order2, transform2)
+        });
+
+        let keys: Vec<SpriteBatchKey> = sprites
+            .iter()
+            .map(|(_, _, sprite)| SpriteBatchKey::new(sprite))
+            .collect();
+        for batch in batch_ranges(&keys) {
+            let batched = &sprites[batch];
+            let mut sprite_batch = SpriteBatch::new(batched[0].2.image.clone());
+            for (render_order, transform, sprite) in batched {
+                let (dest, rotation, zoom) = if render_order.layer == RenderLayer::Ui {
+                    (na::Point2::from(transform.translation.vector), 0.0, 1.0)
+                } else {
+                    (
+                        camera.world_to_screen(&na::Point2::from(transform.translation.vector)),
+                        camera.rotation,
+                        camera.zoom,
                     )
-                    .into(),
-                    rotation: transform.rotation.angle() as f32,
-                    scale: na::Vector2::new(1 as f32, 1 as f32).into(),
+                };
+                sprite_batch.add(DrawParam {
+                    src: sprite.src,
+                    dest: na::Point2::new(dest.x as f32, dest.y as f32).into(),
+                    rotation: (transform.rotation.angle() - rotation) as f32,
+                    scale: na::Vector2::new(zoom as f32, zoom as f32).into(),
                     offset: na::Point2::new(0.5, 0.5).into(),
                     ..Default::default()
-                },
-            )
-            .expect(&format!("Failed drawing sprite {:?}", sprite.image));
+                });
+            }
+            if let Err(e) = graphics::draw(self.context, &sprite_batch, DrawParam::default()) {
+                log::warn!("Failed drawing sprites {}: {}", batched[0].2.path, e);
+            }
         }
         if options.draw_colliders {
-            for (transform, collider_handle) in (&transform_storage, &collider_storage).join() {
-                let transform = (*bodies)
-                    .0
-                    .rigid_body(transform.0)
-                    .expect("Body handle unusable for drawing!")
-                    .position();
-                let collider = (*colliders)
-                    .0
-                    .get(collider_handle.0)
-                    .expect("Collider handle unusable for drawing!");
-                let mut color = Color::new(0.0, 1.0, 0.0, 1.0);
-                if let Some(contacts) =
-                    geometrical_world
-                        .0
-                        .contacts_with(&colliders.0, collider_handle.0, true)
-                {
-                    if let Some(_) = contacts.peekable().peek() {
-                        color.g = 0.0;
-                        color.r = 1.0;
+            for (transform, collider_component, previous_transform) in (
+                &transform_storage,
+                &collider_storage,
+                previous_transform_storage.maybe(),
+            )
+                .join()
+            {
+                let transform = match (*bodies).0.rigid_body(transform.0) {
+                    Some(body) => body.position(),
+                    None => {
+                        log::warn!("Skipping colliders of stale body {:?}", transform.0);
+                        continue;
+                    }
+                };
+                let transform = interpolate(previous_transform, transform, alpha);
+                for collider_handle in &collider_component.0 {
+                    let collider = match (*colliders).0.get(*collider_handle) {
+                        Some(collider) => collider,
+                        None => {
+                            log::warn!("Skipping stale collider {:?}", collider_handle);
+                            continue;
+                        }
+                    };
+                    let mut color = Color::new(0.0, 1.0, 0.0, 1.0);
+                    if collider.is_sensor() {
+                        color = Color::new(0.0, 0.5, 1.0, 1.0);
+                    } else if let Some(contacts) =
+                        geometrical_world
+                            .0
+                            .contacts_with(&colliders.0, *collider_handle, true)
+                    {
+                        if let Some(_) = contacts.peekable().peek() {
+                            color.g = 0.0;
+                            color.r = 1.0;
+                        }
+                    }
+                    let aabb = collider
+                        .shape()
+                        .aabb(&(transform * collider.position_wrt_body()));
+                    let aabb_mins = camera.world_to_screen(aabb.mins());
+                    let aabb_half_extents = aabb.half_extents();
+
+                    // Built in world units and scaled by the draw call, so it rotates with the camera
+                    let drawn = graphics::Mesh::new_rectangle(
+                        self.context,
+                        graphics::DrawMode::stroke(1.0),
+                        Rect::new(
+                            0.0,
+                            0.0,
+                            (aabb_half_extents.x * 2.0) as f32,
+                            (aabb_half_extents.y * 2.0) as f32,
+                        ),
+                        color,
+                    )
+                    .and_then(|rectangle| {
+                        graphics::draw(
+                            self.context,
+                            &rectangle,
+                            DrawParam {
+                                dest: na::Point2::new(aabb_mins.x as f32, aabb_mins.y as f32)
+                                    .into(),
+                                rotation: -camera.rotation as f32,
+                                scale: na::Vector2::new(camera.zoom as f32, camera.zoom as f32)
+                                    .into(),
+                                ..Default::default()
+                            },
+                        )
+                    });
+                    if let Err(e) = drawn {
+                        log::warn!("Failed drawing collider rectangle: {}", e);
                     }
                 }
-                let aabb = collider.shape().aabb(transform);
-                let aabb_mins = aabb.mins();
-                let aabb_half_extents = aabb.half_extents();
+            }
+        }
+    }
+}
+
+/// Identifies the texture a sprite is drawn with, without touching the GPU. Sprites with equal keys
+/// can be drawn in a single `SpriteBatch`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpriteBatchKey<'s> {
+    /// The cache key of the sprite's texture, which the asset cache loads a single texture for.
+    pub texture: &'s str,
+    pub filter: FilterMode,
+}
 
-                let rectangle = graphics::Mesh::new_rectangle(
-                    self.context,
-                    graphics::DrawMode::stroke(1.0),
-                    Rect::new(
-                        aabb_mins.x as f32,
-                        aabb_mins.y as f32,
-                        (aabb_half_extents.x * 2.0) as f32,
-                        (aabb_half_extents.y * 2.0) as f32,
-                    ),
-                    color,
-                )
-                .expect("Creating collider rectangle failed!");
+impl<'s> SpriteBatchKey<'s> {
+    pub fn new(sprite: &'s Sprite) -> SpriteBatchKey<'s> {
+        SpriteBatchKey {
+            texture: &sprite.texture,
+            filter: sprite.image.filter(),
+        }
+    }
+}
 
-                graphics::draw(self.context, &rectangle, (na::Point2::new(0.0, 0.0),))
-                    .expect("Drawing collider rectangle failed!");
+/// Splits sprites, already in draw order, into runs sharing a batch key. Only neighbouring sprites
+/// are grouped so batching never changes what is drawn on top.
+pub fn batch_ranges<K: PartialEq>(keys: &[K]) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    for index in 1..=keys.len() {
+        if index == keys.len() || keys[index] != keys[start] {
+            ranges.push(start..index);
+            start = index;
+        }
+    }
+    ranges
+}
+
+/// Orders sprites back to front by layer, then z, then y within y-sorted layers.
+fn draw_order(
+    order1: &RenderOrder,
+    transform1: &Isometry<f64>,
+    order2: &RenderOrder,
+    transform2: &Isometry<f64>,
+) -> Ordering {
+    order1
+        .layer
+        .cmp(&order2.layer)
+        .then(order1.z.cmp(&order2.z))
+        .then_with(|| {
+            if order1.layer.y_sorted() {
+                let y1 = transform1.translation.vector.y;
+                let y2 = transform2.translation.vector.y;
+                y1.partial_cmp(&y2).unwrap_or(Ordering::Equal)
+            } else {
+                Ordering::Equal
             }
+        })
+}
+
+/// Blends the previous and current physics positions so motion stays smooth between fixed ticks.
+pub(crate) fn interpolate(
+    previous: Option<&PreviousTransform>,
+    current: &Isometry<f64>,
+    alpha: f64,
+) -> Isometry<f64> {
+    match previous {
+        Some(previous) => {
+            let previous = &previous.0;
+            let translation = previous.translation.vector
+                + (current.translation.vector - previous.translation.vector) * alpha;
+            let rotation =
+                previous.rotation.angle() + previous.rotation.angle_to(&current.rotation) * alpha;
+            Isometry::new(translation, rotation)
         }
+        None => *current,
     }
 }
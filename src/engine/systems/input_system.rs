@@ -1,48 +1,41 @@
+use crate::bindings::*;
 use crate::resources::*;
-use ggez::input::keyboard::*;
 use specs::*;
-use std::collections::HashSet;
 
 pub struct InputSystem;
 
 impl<'a> System<'a> for InputSystem {
     type SystemData = (
         ReadExpect<'a, InputContext>,
+        Write<'a, KeyBindings>,
         Write<'a, ActionContext>,
         Write<'a, GameOptions>,
     );
-    fn run(&mut self, (input_context, mut action_context, mut options): Self::SystemData) {
-        let pressed_keys = &input_context.pressed_keys;
-        let last_pressed_keys = &input_context.last_pressed_keys;
-        let active_mods = &input_context.active_mods;
+    fn run(
+        &mut self,
+        (input_context, mut key_bindings, mut action_context, mut options): Self::SystemData,
+    ) {
+        let actions: Vec<PlayerAction> = action_context.player_action_map.keys().cloned().collect();
+        if key_bindings.pending_rebind.is_some() {
+            // Input used to rebind shouldn't also trigger whatever it was bound to before, and
+            // actions held when the rebind started are let go
+            key_bindings.capture_rebind(&input_context);
+            for action in actions {
+                action_context.set_action(action, 0.0);
+            }
+            return;
+        }
 
-        InputSystem::map_keys(
-            &[KeyCode::Up, KeyCode::W].iter().cloned().collect(),
-            PlayerAction::MoveNorth,
-            &mut action_context,
-            pressed_keys,
-        );
-        InputSystem::map_keys(
-            &[KeyCode::Down, KeyCode::S].iter().cloned().collect(),
-            PlayerAction::MoveSouth,
-            &mut action_context,
-            pressed_keys,
-        );
-        InputSystem::map_keys(
-            &[KeyCode::Left, KeyCode::A].iter().cloned().collect(),
-            PlayerAction::MoveWest,
-            &mut action_context,
-            pressed_keys,
-        );
-        InputSystem::map_keys(
-            &[KeyCode::Right, KeyCode::D].iter().cloned().collect(),
-            PlayerAction::MoveEast,
-            &mut action_context,
-            pressed_keys,
-        );
+        for action in actions {
+            InputSystem::map_keys(
+                key_bindings.bindings_for(&Action::Player(action.clone())),
+                action,
+                &mut action_context,
+                &input_context,
+            );
+        }
 
-        if pressed_keys.contains(&KeyCode::F1) && !last_pressed_keys.contains(&KeyCode::F1) {
-            dbg!(&pressed_keys);
+        if key_bindings.is_just_active(&Action::ToggleColliders, &input_context) {
             options.draw_colliders = !options.draw_colliders;
         }
     }
@@ -50,15 +43,15 @@ impl<'a> System<'a> for InputSystem {
 
 impl InputSystem {
     fn map_keys(
-        keys: &HashSet<KeyCode>,
+        bindings: &[Binding],
         action: PlayerAction,
         action_context: &mut ActionContext,
-        pressed_keys: &HashSet<KeyCode>,
+        input_context: &InputContext,
     ) {
-        if pressed_keys.intersection(keys).count() > 0 {
-            action_context.player_action_map.insert(action, true);
-        } else {
-            action_context.player_action_map.insert(action, false);
-        }
+        let value = bindings
+            .iter()
+            .map(|binding| binding.value(input_context))
+            .fold(0.0, f64::max);
+        action_context.set_action(action, value);
     }
 }
@@ -1,3 +1,9 @@
 pub mod action_system;
+pub mod animation_system;
+pub mod camera_system;
+pub mod character_controller_system;
+pub mod collision_event_system;
 pub mod draw_system;
-pub mod input_system;
\ No newline at end of file
+pub mod input_system;
+pub mod penetration_system;
+pub mod rope_system;
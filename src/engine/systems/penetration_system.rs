@@ -0,0 +1,80 @@
+use crate::physics::resources::*;
+use nalgebra::Vector2;
+use nphysics2d::math::Isometry;
+use nphysics2d::object::*;
+use specs::*;
+use std::collections::HashMap;
+
+/// Overlap tolerated before bodies are pushed apart, so resting contacts don't jitter.
+const ALLOWED_PENETRATION: f64 = 0.01;
+
+/// Pushes dynamic bodies out of whatever they overlap after the physics step, along the contact
+/// normal by the penetration depth. Two dynamic bodies are each moved half the way, a dynamic body
+/// overlapping a static or kinematic one is moved all the way. Velocity into the contact is
+/// removed as well, so bodies pushing into each other can't stay stuck.
+pub struct PenetrationSystem;
+
+impl<'a> System<'a> for PenetrationSystem {
+    type SystemData = (
+        Read<'a, MyGeometricalWorld>,
+        Read<'a, MyColliderSet>,
+        Write<'a, MyBodySet>,
+    );
+    fn run(&mut self, (geometrical_world, colliders, mut bodies): Self::SystemData) {
+        let is_dynamic = |bodies: &MyBodySet, handle| {
+            bodies
+                .0
+                .rigid_body(handle)
+                .map_or(false, |body| body.status() == BodyStatus::Dynamic)
+        };
+
+        let mut corrections: HashMap<DefaultBodyHandle, Vector2<f64>> = HashMap::new();
+        for (_, collider1, _, collider2, _, manifold) in
+            geometrical_world.0.contact_pairs(&colliders.0, true)
+        {
+            let contact = match manifold.deepest_contact() {
+                Some(tracked_contact) => &tracked_contact.contact,
+                None => continue,
+            };
+            let depth = contact.depth - ALLOWED_PENETRATION;
+            if depth <= 0.0 || collider1.body() == collider2.body() {
+                continue;
+            }
+
+            let (body1, body2) = (collider1.body(), collider2.body());
+            let (dynamic1, dynamic2) = (is_dynamic(&bodies, body1), is_dynamic(&bodies, body2));
+            let share = match (dynamic1, dynamic2) {
+                (true, true) => 0.5,
+                (false, false) => continue,
+                _ => 1.0,
+            };
+            // The normal points from the first collider towards the second
+            let push = contact.normal.into_inner() * depth * share;
+            if dynamic1 {
+                *corrections.entry(body1).or_insert_with(Vector2::zeros) -= push;
+            }
+            if dynamic2 {
+                *corrections.entry(body2).or_insert_with(Vector2::zeros) += push;
+            }
+        }
+
+        for (handle, correction) in corrections {
+            let body = match bodies.0.rigid_body_mut(handle) {
+                Some(body) => body,
+                None => continue,
+            };
+            let position = *body.position();
+            body.set_position(Isometry::new(
+                position.translation.vector + correction,
+                position.rotation.angle(),
+            ));
+
+            let direction = correction.normalize();
+            let velocity = body.velocity().linear;
+            let towards_contact = velocity.dot(&direction);
+            if towards_contact < 0.0 {
+                body.set_linear_velocity(velocity - direction * towards_contact);
+            }
+        }
+    }
+}
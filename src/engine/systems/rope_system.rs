@@ -0,0 +1,88 @@
+use crate::components::*;
+use crate::physics::resources::*;
+use nalgebra::Vector2;
+use nphysics2d::math::Isometry;
+use nphysics2d::object::*;
+use specs::*;
+
+/// Pulls bodies connected by a rope joint back together when the rope is stretched past its
+/// length, moving dynamic bodies like `PenetrationSystem` does and removing velocity that would
+/// stretch the rope further.
+pub struct RopeSystem;
+
+impl<'a> System<'a> for RopeSystem {
+    type SystemData = (
+        ReadStorage<'a, JointComponent>,
+        ReadStorage<'a, TransformComponent>,
+        Write<'a, MyBodySet>,
+    );
+    fn run(&mut self, (joints, transforms, mut bodies): Self::SystemData) {
+        for joint in joints.join() {
+            let (anchor1, anchor2, length) = match joint.joint {
+                Joint::Rope {
+                    anchor1,
+                    anchor2,
+                    length,
+                } => (anchor1, anchor2, length),
+                Joint::Constraint(_) => continue,
+            };
+            let (body1, body2) =
+                match (transforms.get(joint.entity1), transforms.get(joint.entity2)) {
+                    (Some(transform1), Some(transform2)) => (transform1.0, transform2.0),
+                    _ => continue,
+                };
+            let (position1, dynamic1) = match body_state(&bodies, body1) {
+                Some(state) => state,
+                None => continue,
+            };
+            let (position2, dynamic2) = match body_state(&bodies, body2) {
+                Some(state) => state,
+                None => continue,
+            };
+
+            let offset = position2 * anchor2 - position1 * anchor1;
+            let distance = offset.norm();
+            if distance <= length {
+                continue;
+            }
+            let share = match (dynamic1, dynamic2) {
+                (true, true) => 0.5,
+                (false, false) => continue,
+                _ => 1.0,
+            };
+            let direction = offset / distance;
+            let stretch = distance - length;
+            if dynamic1 {
+                pull(&mut bodies, body1, direction * stretch * share);
+            }
+            if dynamic2 {
+                pull(&mut bodies, body2, -direction * stretch * share);
+            }
+        }
+    }
+}
+
+fn body_state(bodies: &MyBodySet, handle: DefaultBodyHandle) -> Option<(Isometry<f64>, bool)> {
+    let body = bodies.0.rigid_body(handle)?;
+    Some((*body.position(), body.status() == BodyStatus::Dynamic))
+}
+
+/// Moves the body by the correction and removes its velocity against it.
+fn pull(bodies: &mut MyBodySet, handle: DefaultBodyHandle, correction: Vector2<f64>) {
+    let body = match bodies.0.rigid_body_mut(handle) {
+        Some(body) => body,
+        None => return,
+    };
+    let position = *body.position();
+    body.set_position(Isometry::new(
+        position.translation.vector + correction,
+        position.rotation.angle(),
+    ));
+
+    let direction = correction.normalize();
+    let velocity = body.velocity().linear;
+    let away_from_rope = velocity.dot(&direction);
+    if away_from_rope < 0.0 {
+        body.set_linear_velocity(velocity - direction * away_from_rope);
+    }
+}
@@ -1,4 +1,5 @@
 use engine::components::*;
+use engine::error::EngineError;
 use engine::*;
 use nalgebra::Vector2;
 use ncollide2d::shape::Cuboid;
@@ -6,17 +7,46 @@ use ncollide2d::shape::ShapeHandle;
 
 fn main() {
     // Create a new game and run it.
-    let mut game = engine::new_game_state("Game Project", (800.0, 800.0));
-    let player = create_player(&mut game);
+    let mut game = match engine::new_game_state("Game Project", (800.0, 800.0)) {
+        Ok(game) => game,
+        Err(e) => {
+            println!("Failed starting the game: {}", e);
+            return;
+        }
+    };
+    match engine::assets_folder(&game) {
+        Some(assets) => {
+            if let Err(e) = engine::load_bindings(&mut game, assets.join("bindings.ron")) {
+                println!("Failed loading key bindings, using defaults: {}", e);
+            }
+        }
+        None => println!("No assets folder found, using default key bindings"),
+    }
+    if cfg!(debug_assertions) {
+        if let Err(e) = engine::watch_assets(&mut game) {
+            println!("Failed watching assets, hot reloading is disabled: {}", e);
+        }
+    }
+    let player = match create_player(&mut game) {
+        Ok(player) => player,
+        Err(e) => {
+            println!("Failed creating the player: {}", e);
+            return;
+        }
+    };
     dbg!(&player);
-    engine::run(&mut game);
+    engine::set_camera_target(&mut game, Some(player));
+    if let Err(e) = engine::run(&mut game) {
+        println!("Error occurred: {}", e);
+    }
 }
 
-fn create_player(game: &mut GameState) -> Entity {
-    let mut image = engine::load_image(game, "\\othersprite.png");
-    image.set_filter(FilterMode::Nearest);
+fn create_player(game: &mut GameState) -> Result<Entity, EngineError> {
+    let path = "/othersprite.png";
+    let mut sprite = engine::load_sprite(game, path, None)?;
+    sprite.image.set_filter(FilterMode::Nearest);
     let player = engine::create_entity(game, 200.0, 200.0, 0.0)
-        .with(Sprite { image })
+        .with(sprite)
         .with(Player {
             movement_speed: 1000.0,
         })
@@ -25,6 +55,6 @@ fn create_player(game: &mut GameState) -> Entity {
         game,
         player,
         ShapeHandle::new(Cuboid::new(Vector2::new(10f64, 20f64))),
-    );
-    player
+    )?;
+    Ok(player)
 }
@@ -0,0 +1,208 @@
+use engine::animation::*;
+use engine::assets::Frame;
+use engine::components::{Animation, MovementClips, Player, Sprite};
+use engine::*;
+use specs::shrev::EventChannel;
+use specs::WorldExt;
+use std::collections::HashMap;
+
+fn animation(mode: PlaybackMode) -> Animation {
+    let mut clips = HashMap::new();
+    clips.insert(
+        "attack".to_string(),
+        AnimationClip::uniform(&["attack_1", "attack_2", "attack_3"], 0.1, mode),
+    );
+    clips.insert(
+        "idle".to_string(),
+        AnimationClip::uniform(&["idle"], 1.0, PlaybackMode::Loop),
+    );
+    Animation::new(clips, "attack")
+}
+
+#[test]
+fn frames_advance_by_their_duration() {
+    let mut animation = animation(PlaybackMode::Loop);
+    assert_eq!(animation.current_frame(), Some("attack_1"));
+    animation.advance(0.15);
+    assert_eq!(animation.current_frame(), Some("attack_2"));
+    animation.advance(0.1);
+    assert_eq!(animation.current_frame(), Some("attack_3"));
+}
+
+#[test]
+fn looping_clips_start_over() {
+    let mut animation = animation(PlaybackMode::Loop);
+    assert!(animation.advance(0.35));
+    assert_eq!(animation.current_frame(), Some("attack_1"));
+    assert!(!animation.is_finished());
+}
+
+#[test]
+fn one_shot_clips_hold_their_last_frame() {
+    let mut animation = animation(PlaybackMode::Once);
+    assert!(animation.advance(0.5));
+    assert_eq!(animation.current_frame(), Some("attack_3"));
+    assert!(animation.is_finished());
+    assert!(!animation.advance(0.5));
+}
+
+#[test]
+fn switching_clips_starts_from_the_first_frame() {
+    let mut animation = animation(PlaybackMode::Loop);
+    animation.advance(0.15);
+    assert!(animation.play("idle"));
+    assert_eq!(animation.current_frame(), Some("idle"));
+    assert!(!animation.play("walk_north"));
+    assert_eq!(animation.current_clip(), "idle");
+}
+
+#[test]
+fn shortened_clips_start_over() {
+    let mut animation = animation(PlaybackMode::Loop);
+    animation.advance(0.25);
+    assert_eq!(animation.current_frame(), Some("attack_3"));
+    animation.clips.insert(
+        "attack".to_string(),
+        AnimationClip::uniform(&["attack_1"], 0.1, PlaybackMode::Loop),
+    );
+
+    animation.advance(0.01);
+    assert_eq!(animation.current_frame(), Some("attack_1"));
+}
+
+#[test]
+fn frames_with_a_negative_duration_are_skipped() {
+    let frame = |name: &str, duration: f64| AnimationFrame {
+        frame: name.to_string(),
+        duration,
+    };
+    let mut clips = HashMap::new();
+    clips.insert(
+        "blink".to_string(),
+        AnimationClip {
+            frames: vec![
+                frame("open", 0.1),
+                frame("closed", -0.1),
+                frame("open_again", 0.1),
+            ],
+            mode: PlaybackMode::Loop,
+        },
+    );
+    let mut animation = Animation::new(clips, "blink");
+    assert!(!animation.advance(0.15));
+    assert_eq!(animation.current_frame(), Some("open_again"));
+}
+
+#[test]
+fn clips_with_frames_that_are_not_positive_fail_to_load() {
+    let loaded: Result<AnimationClip, _> =
+        ron::de::from_str("(frames: [(frame: \"idle\", duration: 0.0)])");
+    assert!(loaded.is_err());
+    let loaded: Result<AnimationClip, _> =
+        ron::de::from_str("(frames: [(frame: \"idle\", duration: 0.5)])");
+    assert_eq!(
+        loaded.expect("Failed loading clip"),
+        AnimationClip::uniform(&["idle"], 0.5, PlaybackMode::Loop)
+    );
+}
+
+#[test]
+fn finished_clips_are_reported() {
+    let mut game = engine::new_headless_game_state();
+    let entity = engine::create_entity(&mut game, 0.0, 0.0, 0.0).build();
+    engine::add_animation(&mut game, entity, animation(PlaybackMode::Once))
+        .expect("Failed adding animation");
+    let mut reader = engine::world_mut(&mut game)
+        .write_resource::<EventChannel<AnimationEvent>>()
+        .register_reader();
+
+    engine::step(&mut game, 60);
+
+    let world = engine::world(&game);
+    let events: Vec<AnimationEvent> = world
+        .read_resource::<EventChannel<AnimationEvent>>()
+        .read(&mut reader)
+        .cloned()
+        .collect();
+    assert_eq!(
+        events,
+        vec![AnimationEvent::ClipFinished {
+            entity,
+            clip: "attack".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn movement_clips_wait_for_one_shot_clips() {
+    let mut game = engine::new_headless_game_state();
+    let entity = engine::create_entity(&mut game, 0.0, 0.0, 0.0)
+        .with(Player {
+            movement_speed: 100.0,
+        })
+        .with(MovementClips {
+            idle: Some("idle".to_string()),
+            ..Default::default()
+        })
+        .build();
+    engine::add_animation(&mut game, entity, animation(PlaybackMode::Once))
+        .expect("Failed adding animation");
+
+    engine::step(&mut game, 1);
+    assert_eq!(current_clip(&game, entity), "attack");
+
+    // The attack takes 0.3 seconds, which is 18 ticks
+    engine::step(&mut game, 20);
+    assert_eq!(current_clip(&game, entity), "idle");
+}
+
+// Sprites need a graphics context, run with `cargo test -- --ignored` where a window can be opened
+#[test]
+#[ignore]
+fn frames_are_shown_on_the_sprite() {
+    let mut game =
+        engine::new_game_state("Animation test", (64.0, 64.0)).expect("Failed creating window");
+    let sprite =
+        engine::load_sprite(&mut game, "/othersprite.png", None).expect("Failed loading sprite");
+    let (width, height) = (sprite.image.width(), sprite.image.height());
+    let entity = engine::create_entity(&mut game, 0.0, 0.0, 0.0)
+        .with(sprite)
+        .build();
+    let mut clips = HashMap::new();
+    clips.insert(
+        "blink".to_string(),
+        AnimationClip::uniform(&["top", "bottom"], 0.1, PlaybackMode::Loop),
+    );
+    engine::add_animation(&mut game, entity, Animation::new(clips, "blink"))
+        .expect("Failed adding animation");
+
+    engine::step(&mut game, 1);
+    let top = Frame {
+        x: 0,
+        y: 0,
+        w: 16,
+        h: 18,
+    };
+    assert_eq!(sprite_src(&game, entity), top.to_src(width, height));
+
+    engine::step(&mut game, 6);
+    let bottom = Frame { y: 18, ..top };
+    assert_eq!(sprite_src(&game, entity), bottom.to_src(width, height));
+}
+
+fn current_clip(game: &GameState, entity: Entity) -> String {
+    engine::world(game)
+        .read_component::<Animation>()
+        .get(entity)
+        .expect("Entity has no animation")
+        .current_clip()
+        .to_string()
+}
+
+fn sprite_src(game: &GameState, entity: Entity) -> ggez::graphics::Rect {
+    engine::world(game)
+        .read_component::<Sprite>()
+        .get(entity)
+        .expect("Entity has no sprite")
+        .src
+}
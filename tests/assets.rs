@@ -0,0 +1,84 @@
+use engine::assets::{atlas_path, normalize_path, Frame, TextureAtlas};
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn equivalent_paths_are_normalized_alike() {
+    assert_eq!(normalize_path("sprite.png"), "/sprite.png");
+    assert_eq!(normalize_path("/sprite.png"), "/sprite.png");
+    assert_eq!(normalize_path("./sprite.png"), "/sprite.png");
+    assert_eq!(normalize_path("\\\\sprite.png"), "/sprite.png");
+    assert_eq!(
+        normalize_path("characters//./player.png"),
+        "/characters/player.png"
+    );
+}
+
+#[test]
+fn parent_segments_are_resolved() {
+    assert_eq!(normalize_path("/characters/../sprite.png"), "/sprite.png");
+    assert_eq!(
+        normalize_path("characters\\enemies\\..\\player.png"),
+        "/characters/player.png"
+    );
+    assert_eq!(normalize_path("/characters/player/.."), "/characters");
+}
+
+#[test]
+fn parent_segments_do_not_leave_the_assets_folder() {
+    assert_eq!(normalize_path("/../sprite.png"), "/sprite.png");
+    assert_eq!(normalize_path("../../sprite.png"), "/sprite.png");
+    assert_eq!(normalize_path(".."), "/");
+}
+
+#[test]
+fn atlases_are_next_to_their_image() {
+    assert_eq!(atlas_path("/characters.png"), "/characters.ron");
+    assert_eq!(
+        atlas_path("sheets\\player.sheet.png"),
+        "/sheets/player.sheet.ron"
+    );
+    assert_eq!(atlas_path("/sheets/player"), "/sheets/player.ron");
+    assert_eq!(atlas_path("/sheets.v2/player"), "/sheets.v2/player.ron");
+}
+
+#[test]
+fn frames_are_fractions_of_the_image() {
+    let frame = Frame {
+        x: 16,
+        y: 8,
+        w: 32,
+        h: 24,
+    };
+    let src = frame.to_src(64, 32);
+    assert_eq!((src.x, src.y, src.w, src.h), (0.25, 0.25, 0.5, 0.75));
+
+    let whole = Frame {
+        x: 0,
+        y: 0,
+        w: 15,
+        h: 24,
+    };
+    let src = whole.to_src(15, 24);
+    assert_eq!((src.x, src.y, src.w, src.h), (0.0, 0.0, 1.0, 1.0));
+}
+
+#[test]
+fn atlas_files_describe_their_frames() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("assets")
+        .join(&atlas_path("/othersprite.png")[1..]);
+    let file = fs::File::open(path).expect("Failed opening atlas");
+    let atlas: TextureAtlas = ron::de::from_reader(file).expect("Failed reading atlas");
+
+    assert_eq!(
+        atlas.frames.get("bottom"),
+        Some(&Frame {
+            x: 0,
+            y: 18,
+            w: 16,
+            h: 18,
+        })
+    );
+    assert_eq!(atlas.frames.len(), 2);
+}
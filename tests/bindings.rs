@@ -0,0 +1,188 @@
+use engine::bindings::{Action, AxisDirection, Binding, KeyBindings, Modifier};
+use engine::resources::{InputContext, PlayerAction};
+use ggez::event::{Axis, Button, KeyCode, KeyMods, MouseButton};
+
+fn stick_at(x: f32) -> InputContext {
+    let mut input_context = InputContext::default();
+    input_context.gamepad_axes.insert(Axis::LeftStickX, x);
+    input_context
+}
+
+#[test]
+fn drifting_stick_does_not_hold_actions() {
+    let bindings = KeyBindings::default();
+    let east = Action::Player(PlayerAction::MoveEast);
+    let west = Action::Player(PlayerAction::MoveWest);
+
+    for drift in &[0.05, -0.05, 0.15, -0.15] {
+        let input_context = stick_at(*drift);
+        assert!(!bindings.is_active(&east, &input_context));
+        assert!(!bindings.is_active(&west, &input_context));
+        assert_eq!(bindings.value(&east, &input_context), 0.0);
+    }
+}
+
+#[test]
+fn deflected_stick_gives_its_deflection() {
+    let positive = Binding::GamepadAxis(Axis::LeftStickX, AxisDirection::Positive);
+    let negative = Binding::GamepadAxis(Axis::LeftStickX, AxisDirection::Negative);
+
+    let input_context = stick_at(0.6);
+    assert_eq!(positive.value(&input_context), f64::from(0.6f32));
+    assert_eq!(negative.value(&input_context), 0.0);
+
+    let input_context = stick_at(-0.6);
+    assert_eq!(positive.value(&input_context), 0.0);
+    assert_eq!(negative.value(&input_context), f64::from(0.6f32));
+}
+
+#[test]
+fn stick_pushed_past_the_threshold_is_just_active_once() {
+    let bindings = KeyBindings::default();
+    let east = Action::Player(PlayerAction::MoveEast);
+    let mut input_context = stick_at(0.1);
+    assert!(!bindings.is_just_active(&east, &input_context));
+
+    input_context.end_tick();
+    input_context.gamepad_axes.insert(Axis::LeftStickX, 0.8);
+    assert!(bindings.is_just_active(&east, &input_context));
+
+    input_context.end_tick();
+    assert!(!bindings.is_just_active(&east, &input_context));
+}
+
+/// Captures the rebind of `MoveNorth` after the input is given a tick after nothing was held.
+fn rebind_to(input: impl FnOnce(&mut InputContext)) -> (KeyBindings, Vec<Binding>) {
+    let north = Action::Player(PlayerAction::MoveNorth);
+    let mut bindings = KeyBindings::default();
+    let mut input_context = InputContext::default();
+    input_context.end_tick();
+    input(&mut input_context);
+    bindings.rebind_next(north.clone());
+    bindings.capture_rebind(&input_context);
+    let captured = bindings.bindings_for(&north).to_vec();
+    (bindings, captured)
+}
+
+#[test]
+fn bindings_are_loaded_as_saved() {
+    let path = std::env::temp_dir().join("engine_bindings_test.ron");
+    let mut bindings = KeyBindings::default();
+    bindings.bind(
+        Action::ToggleColliders,
+        Binding::Chord(vec![Modifier::Ctrl, Modifier::Shift], KeyCode::C),
+    );
+    bindings.bind(
+        Action::Player(PlayerAction::MoveNorth),
+        Binding::Mouse(MouseButton::Left),
+    );
+    bindings.save(&path).expect("Failed saving bindings");
+    let loaded = KeyBindings::load(&path).expect("Failed loading bindings");
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(loaded.bindings, bindings.bindings);
+}
+
+#[test]
+fn bindings_in_the_assets_folder_load() {
+    let mut game = engine::new_headless_game_state();
+    let assets = engine::assets_folder(&game).expect("No assets folder found");
+    engine::load_bindings(&mut game, assets.join("bindings.ron")).expect("Failed loading bindings");
+}
+
+#[test]
+fn rebinding_captures_the_next_key() {
+    let (bindings, captured) = rebind_to(|input_context| {
+        input_context.pressed_keys.insert(KeyCode::K);
+    });
+    assert_eq!(captured, vec![Binding::Key(KeyCode::K)]);
+    assert!(bindings.pending_rebind.is_none());
+}
+
+#[test]
+fn rebinding_captures_chords() {
+    let (_, captured) = rebind_to(|input_context| {
+        input_context.pressed_keys.insert(KeyCode::LControl);
+        input_context.pressed_keys.insert(KeyCode::K);
+        input_context.active_mods = KeyMods::CTRL;
+    });
+    assert_eq!(
+        captured,
+        vec![Binding::Chord(vec![Modifier::Ctrl], KeyCode::K)]
+    );
+}
+
+#[test]
+fn rebinding_waits_past_modifiers_on_their_own() {
+    let north = Action::Player(PlayerAction::MoveNorth);
+    let (mut bindings, captured) = rebind_to(|input_context| {
+        input_context.pressed_keys.insert(KeyCode::LShift);
+        input_context.active_mods = KeyMods::SHIFT;
+    });
+    assert_eq!(captured, KeyBindings::default().bindings_for(&north));
+    assert_eq!(bindings.pending_rebind, Some(north.clone()));
+
+    let mut input_context = InputContext::default();
+    input_context.pressed_keys.insert(KeyCode::LShift);
+    input_context.active_mods = KeyMods::SHIFT;
+    input_context.end_tick();
+    input_context.pressed_keys.insert(KeyCode::LShift);
+    input_context.pressed_keys.insert(KeyCode::K);
+    input_context.active_mods = KeyMods::SHIFT;
+    bindings.capture_rebind(&input_context);
+    assert_eq!(
+        bindings.bindings_for(&north),
+        &[Binding::Chord(vec![Modifier::Shift], KeyCode::K)]
+    );
+}
+
+#[test]
+fn rebinding_captures_mouse_and_gamepad_input() {
+    let (_, captured) = rebind_to(|input_context| {
+        input_context.mouse_buttons.insert(MouseButton::Right);
+    });
+    assert_eq!(captured, vec![Binding::Mouse(MouseButton::Right)]);
+
+    let (_, captured) = rebind_to(|input_context| {
+        input_context.gamepad_buttons.insert(Button::South);
+    });
+    assert_eq!(captured, vec![Binding::GamepadButton(Button::South)]);
+
+    let (_, captured) = rebind_to(|input_context| {
+        input_context.gamepad_axes.insert(Axis::LeftStickY, -0.8);
+    });
+    assert_eq!(
+        captured,
+        vec![Binding::GamepadAxis(
+            Axis::LeftStickY,
+            AxisDirection::Negative
+        )]
+    );
+}
+
+#[test]
+fn rebinding_ignores_sticks_barely_pushed() {
+    let north = Action::Player(PlayerAction::MoveNorth);
+    let (bindings, captured) = rebind_to(|input_context| {
+        input_context.gamepad_axes.insert(Axis::LeftStickY, 0.3);
+    });
+    assert_eq!(captured, KeyBindings::default().bindings_for(&north));
+    assert_eq!(bindings.pending_rebind, Some(north));
+}
+
+#[test]
+fn bind_adds_each_binding_once_and_unbind_removes_it() {
+    let action = Action::ToggleColliders;
+    let binding = Binding::Key(KeyCode::F2);
+    let mut bindings = KeyBindings::default();
+
+    bindings.bind(action.clone(), binding.clone());
+    bindings.bind(action.clone(), binding.clone());
+    assert_eq!(
+        bindings.bindings_for(&action),
+        &[Binding::Key(KeyCode::F1), binding.clone()]
+    );
+
+    bindings.unbind(&action, &binding);
+    assert_eq!(bindings.bindings_for(&action), &[Binding::Key(KeyCode::F1)]);
+}
@@ -0,0 +1,127 @@
+use engine::resources::Camera;
+use nalgebra::{Point2, Vector2};
+use std::f64::consts::FRAC_PI_2;
+
+const TOLERANCE: f64 = 1e-9;
+
+fn camera() -> Camera {
+    Camera::new(Vector2::new(800.0, 600.0))
+}
+
+fn assert_near(actual: Point2<f64>, expected: Point2<f64>) {
+    assert!(
+        (actual - expected).norm() < TOLERANCE,
+        "Expected {:?}, got {:?}",
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn new_camera_shows_the_world_as_laid_out_on_the_screen() {
+    let camera = camera();
+    let point = Point2::new(120.0, 45.0);
+    assert_near(camera.world_to_screen(&point), point);
+    assert_near(camera.screen_to_world(&point), point);
+}
+
+#[test]
+fn camera_position_is_at_the_center_of_the_screen() {
+    let mut camera = camera();
+    camera.position = Point2::new(-500.0, 1000.0);
+    camera.zoom = 3.0;
+    camera.rotation = 1.0;
+    assert_near(
+        camera.world_to_screen(&Point2::new(-500.0, 1000.0)),
+        Point2::new(400.0, 300.0),
+    );
+}
+
+#[test]
+fn zoom_scales_distances_from_the_center() {
+    let mut camera = camera();
+    camera.zoom = 2.0;
+    let center = camera.position;
+    assert_near(
+        camera.world_to_screen(&(center + Vector2::new(10.0, -5.0))),
+        Point2::new(420.0, 290.0),
+    );
+}
+
+#[test]
+fn rotation_turns_the_view_around_the_center() {
+    let mut camera = camera();
+    camera.rotation = FRAC_PI_2;
+    let center = camera.position;
+    // Turning the camera a quarter clockwise shows what's to its right above the center
+    assert_near(
+        camera.world_to_screen(&(center + Vector2::new(10.0, 0.0))),
+        Point2::new(400.0, 290.0),
+    );
+}
+
+#[test]
+fn screen_to_world_undoes_world_to_screen() {
+    let mut camera = camera();
+    camera.position = Point2::new(100.0, 50.0);
+    camera.zoom = 2.5;
+    camera.rotation = 0.7;
+    for point in &[
+        Point2::new(0.0, 0.0),
+        Point2::new(-30.0, 12.5),
+        Point2::new(640.0, 480.0),
+    ] {
+        assert_near(
+            camera.screen_to_world(&camera.world_to_screen(point)),
+            *point,
+        );
+        assert_near(
+            camera.world_to_screen(&camera.screen_to_world(point)),
+            *point,
+        );
+    }
+}
+
+#[test]
+fn following_is_frame_rate_independent() {
+    let target = Point2::new(1000.0, 0.0);
+    let mut once = camera();
+    once.follow(&target, 0.1);
+    let mut twice = camera();
+    twice.follow(&target, 0.05);
+    twice.follow(&target, 0.05);
+
+    assert_near(once.position, twice.position);
+    assert!(once.position.x > 400.0 && once.position.x < 1000.0);
+}
+
+#[test]
+fn infinite_follow_speed_keeps_the_target_centered() {
+    let mut camera = camera();
+    camera.follow_speed = std::f64::INFINITY;
+    camera.follow(&Point2::new(1000.0, -20.0), 0.0);
+    assert_near(camera.position, Point2::new(1000.0, -20.0));
+}
+
+#[test]
+fn view_is_kept_within_bounds() {
+    let mut camera = camera();
+    camera.bounds = Some((Point2::new(0.0, 0.0), Point2::new(2000.0, 1000.0)));
+    camera.position = Point2::new(-100.0, 900.0);
+    camera.clamp_to_bounds();
+    assert_near(camera.position, Point2::new(400.0, 700.0));
+
+    camera.zoom = 2.0;
+    camera.position = Point2::new(-100.0, 900.0);
+    camera.clamp_to_bounds();
+    assert_near(camera.position, Point2::new(200.0, 850.0));
+}
+
+#[test]
+fn view_is_centered_on_bounds_smaller_than_the_view() {
+    let mut camera = camera();
+    camera.bounds = Some((Point2::new(0.0, 0.0), Point2::new(300.0, 2000.0)));
+    camera.position = Point2::new(1000.0, 1000.0);
+    camera.clamp_to_bounds();
+    assert_near(camera.position, Point2::new(150.0, 1000.0));
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use common::*;
+use engine::components::CharacterController;
+use engine::physics::body::{BodyDescriptor, BodyType};
+use engine::*;
+use nalgebra::Vector2;
+use ncollide2d::shape::{Cuboid, ShapeHandle};
+use specs::WorldExt;
+
+const TOLERANCE: f64 = 0.2;
+
+fn create_character(game: &mut GameState, velocity: Vector2<f64>) -> Entity {
+    let character = create_box(
+        game,
+        0.0,
+        0.0,
+        &BodyDescriptor::new().body_type(BodyType::Kinematic),
+    );
+    engine::world_mut(game)
+        .write_storage::<CharacterController>()
+        .insert(
+            character,
+            CharacterController {
+                desired_velocity: velocity,
+                ..Default::default()
+            },
+        )
+        .expect("Failed adding character controller");
+    character
+}
+
+fn collided(game: &GameState, entity: Entity) -> bool {
+    engine::world(game)
+        .read_component::<CharacterController>()
+        .get(entity)
+        .expect("Entity has no character controller")
+        .collided
+}
+
+#[test]
+fn character_moves_at_its_desired_velocity() {
+    let mut game = engine::new_headless_game_state();
+    let character = create_character(&mut game, Vector2::new(60.0, -30.0));
+
+    // The default timestep runs 60 ticks per second
+    engine::step(&mut game, 60);
+
+    assert!((position(&game, character) - Vector2::new(60.0, -30.0)).norm() < TOLERANCE);
+    assert!(!collided(&game, character));
+}
+
+#[test]
+fn character_stops_at_walls() {
+    let mut game = engine::new_headless_game_state();
+    let character = create_character(&mut game, Vector2::new(600.0, 0.0));
+    let wall = create_box(&mut game, 50.0, 0.0, &BodyDescriptor::fixed());
+
+    engine::step(&mut game, 60);
+
+    let gap = position(&game, wall).x - position(&game, character).x - HALF_EXTENT * 2.0;
+    assert!(gap >= 0.0);
+    assert!(gap < TOLERANCE);
+    assert!(collided(&game, character));
+}
+
+#[test]
+fn character_slides_along_walls() {
+    let mut game = engine::new_headless_game_state();
+    let character = create_character(&mut game, Vector2::new(600.0, 300.0));
+    let wall =
+        engine::create_entity_with(&mut game, 50.0, 0.0, 0.0, &BodyDescriptor::fixed()).build();
+    engine::add_collider(
+        &mut game,
+        wall,
+        ShapeHandle::new(Cuboid::new(Vector2::new(HALF_EXTENT, 100.0))),
+    )
+    .expect("Failed adding collider");
+
+    engine::step(&mut game, 6);
+
+    let moved = position(&game, character);
+    assert!(moved.x < 50.0 - HALF_EXTENT * 2.0);
+    assert!((moved.y - 30.0).abs() < TOLERANCE);
+}
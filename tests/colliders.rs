@@ -0,0 +1,77 @@
+mod common;
+
+use common::*;
+use engine::physics::collider::ColliderDescriptor;
+use engine::physics::query::{PhysicsQuery, QueryFilter};
+use engine::*;
+use nalgebra::{Point2, Vector2};
+use specs::WorldExt;
+
+const TOLERANCE: f64 = 1e-3;
+
+fn create_grouped(game: &mut GameState, x: f64, group: usize) -> Entity {
+    let entity = create_undamped(game, x, 0.0);
+    let descriptor = ColliderDescriptor::new(box_shape())
+        .membership(&[group])
+        .whitelist(&[group]);
+    engine::add_collider_with(game, entity, &descriptor).expect("Failed adding collider");
+    entity
+}
+
+#[test]
+fn bodies_in_groups_that_do_not_interact_pass_through_each_other() {
+    let mut game = engine::new_headless_game_state();
+    let mover = create_grouped(&mut game, -40.0, 1);
+    let other = create_grouped(&mut game, 0.0, 2);
+
+    set_velocity(&mut game, mover, Vector2::new(300.0, 0.0));
+    engine::step(&mut game, 30);
+
+    assert!(position(&game, mover).x > 40.0);
+    assert!((velocity(&game, mover) - Vector2::new(300.0, 0.0)).norm() < 1e-9);
+    assert_eq!(position(&game, other), Vector2::zeros());
+}
+
+#[test]
+fn bodies_in_the_same_group_collide() {
+    let mut game = engine::new_headless_game_state();
+    let mover = create_grouped(&mut game, -40.0, 1);
+    create_grouped(&mut game, 0.0, 1);
+
+    set_velocity(&mut game, mover, Vector2::new(300.0, 0.0));
+    engine::step(&mut game, 30);
+
+    assert!(position(&game, mover).x < 0.0);
+}
+
+#[test]
+fn colliders_are_found_at_their_offset() {
+    let mut game = engine::new_headless_game_state();
+    let entity = create_fixed(&mut game, 0.0, 0.0);
+    for x in &[50.0, -50.0] {
+        let descriptor = ColliderDescriptor::new(box_shape()).offset(*x, 0.0, 0.0);
+        engine::add_collider_with(&mut game, entity, &descriptor).expect("Failed adding collider");
+    }
+    engine::step(&mut game, 1);
+    let query = engine::world(&game).system_data::<PhysicsQuery>();
+    let filter = QueryFilter::new();
+
+    assert!(query
+        .entities_at_point(&Point2::origin(), &filter)
+        .is_empty());
+    assert_eq!(
+        query.entities_at_point(&Point2::new(50.0, 0.0), &filter),
+        vec![entity]
+    );
+    assert_eq!(
+        query.entities_at_point(&Point2::new(-50.0, 0.0), &filter),
+        vec![entity]
+    );
+    for direction in &[Vector2::new(1.0, 0.0), Vector2::new(-1.0, 0.0)] {
+        let hit = query
+            .cast_ray(Point2::origin(), *direction, 1000.0, &filter)
+            .expect("Ray missed");
+        assert_eq!(hit.entity, entity);
+        assert!((hit.toi - 40.0).abs() < TOLERANCE);
+    }
+}
@@ -0,0 +1,100 @@
+//! Fixtures shared by the integration tests. Not every test uses every fixture.
+#![allow(dead_code)]
+
+use engine::components::{Player, TransformComponent};
+use engine::physics::body::BodyDescriptor;
+use engine::physics::resources::MyBodySet;
+use engine::resources::InputContext;
+use engine::*;
+use ggez::event::KeyCode;
+use nalgebra::Vector2;
+use ncollide2d::shape::{Cuboid, ShapeHandle};
+use specs::WorldExt;
+
+/// Half the width and height of the boxes tests collide.
+pub const HALF_EXTENT: f64 = 10.0;
+
+pub fn box_shape() -> ShapeHandle<f64> {
+    ShapeHandle::new(Cuboid::new(Vector2::new(HALF_EXTENT, HALF_EXTENT)))
+}
+
+pub fn add_box(game: &mut GameState, entity: Entity) {
+    engine::add_collider(game, entity, box_shape()).expect("Failed adding collider");
+}
+
+pub fn create_box(game: &mut GameState, x: f64, y: f64, descriptor: &BodyDescriptor) -> Entity {
+    let entity = engine::create_entity_with(game, x, y, 0.0, descriptor).build();
+    add_box(game, entity);
+    entity
+}
+
+pub fn create_player(game: &mut GameState, x: f64, y: f64) -> Entity {
+    let player = engine::create_entity(game, x, y, 0.0)
+        .with(Player {
+            movement_speed: 300.0,
+        })
+        .build();
+    add_box(game, player);
+    player
+}
+
+/// A static body without colliders, to anchor joints and force fields to.
+pub fn create_fixed(game: &mut GameState, x: f64, y: f64) -> Entity {
+    engine::create_entity_with(game, x, y, 0.0, &BodyDescriptor::fixed()).build()
+}
+
+/// A dynamic body without colliders or damping, so forces move it freely.
+pub fn create_undamped(game: &mut GameState, x: f64, y: f64) -> Entity {
+    let descriptor = BodyDescriptor::new().damping(0.0, 0.0);
+    engine::create_entity_with(game, x, y, 0.0, &descriptor).build()
+}
+
+fn body_handle(game: &GameState, entity: Entity) -> nphysics2d::object::DefaultBodyHandle {
+    engine::world(game)
+        .read_component::<TransformComponent>()
+        .get(entity)
+        .expect("Entity has no transform")
+        .0
+}
+
+pub fn position(game: &GameState, entity: Entity) -> Vector2<f64> {
+    let handle = body_handle(game, entity);
+    engine::world(game)
+        .read_resource::<MyBodySet>()
+        .0
+        .rigid_body(handle)
+        .expect("Entity has no body")
+        .position()
+        .translation
+        .vector
+}
+
+pub fn velocity(game: &GameState, entity: Entity) -> Vector2<f64> {
+    let handle = body_handle(game, entity);
+    engine::world(game)
+        .read_resource::<MyBodySet>()
+        .0
+        .rigid_body(handle)
+        .expect("Entity has no body")
+        .velocity()
+        .linear
+}
+
+pub fn set_velocity(game: &mut GameState, entity: Entity, velocity: Vector2<f64>) {
+    let handle = body_handle(game, entity);
+    engine::world_mut(game)
+        .write_resource::<MyBodySet>()
+        .0
+        .rigid_body_mut(handle)
+        .expect("Entity has no body")
+        .set_linear_velocity(velocity);
+}
+
+pub fn set_key(game: &mut GameState, key: KeyCode, pressed: bool) {
+    let mut input_context = engine::world_mut(game).write_resource::<InputContext>();
+    if pressed {
+        input_context.pressed_keys.insert(key);
+    } else {
+        input_context.pressed_keys.remove(&key);
+    }
+}
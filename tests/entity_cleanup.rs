@@ -0,0 +1,229 @@
+use engine::error::EngineError;
+use engine::physics::body::BodyDescriptor;
+use engine::physics::forces::SpringDescriptor;
+use engine::physics::joint::JointDescriptor;
+use engine::physics::resources::*;
+use engine::*;
+use nalgebra::{Point2, Vector2};
+use ncollide2d::shape::{Ball, ShapeHandle};
+use nphysics2d::object::RigidBodyDesc;
+use specs::WorldExt;
+
+fn create_ball(game: &mut GameState, x: f64) -> Entity {
+    let entity = engine::create_entity_with(game, x, 0.0, 0.0, &BodyDescriptor::new()).build();
+    engine::add_collider(game, entity, ShapeHandle::new(Ball::new(5.0)))
+        .expect("Failed adding collider");
+    entity
+}
+
+fn body_count(game: &GameState) -> usize {
+    engine::world(game)
+        .read_resource::<MyBodySet>()
+        .0
+        .iter()
+        .count()
+}
+
+fn collider_count(game: &GameState) -> usize {
+    engine::world(game)
+        .read_resource::<MyColliderSet>()
+        .0
+        .iter()
+        .count()
+}
+
+fn joint_count(game: &GameState) -> usize {
+    engine::world(game)
+        .read_resource::<MyJointConstraintSet>()
+        .0
+        .iter()
+        .count()
+}
+
+fn force_generator_count(game: &GameState) -> usize {
+    engine::world(game)
+        .read_resource::<MyForceGeneratorSet>()
+        .0
+        .iter()
+        .count()
+}
+
+fn spring() -> SpringDescriptor {
+    SpringDescriptor {
+        anchor1: Point2::origin(),
+        anchor2: Point2::origin(),
+        length: 10.0,
+        stiffness: 1.0,
+        damping: 0.1,
+    }
+}
+
+#[test]
+fn destroyed_entities_lose_their_body_and_colliders() {
+    let mut game = engine::new_headless_game_state();
+    let entity = create_ball(&mut game, 0.0);
+    let kept = create_ball(&mut game, 100.0);
+    engine::step(&mut game, 1);
+
+    engine::destroy_entity(&mut game, entity).expect("Failed destroying entity");
+    engine::step(&mut game, 1);
+
+    assert!(!engine::world(&game).is_alive(entity));
+    assert!(engine::world(&game).is_alive(kept));
+    assert_eq!(body_count(&game), 1);
+    assert_eq!(collider_count(&game), 1);
+}
+
+#[test]
+fn destroying_a_destroyed_entity_fails() {
+    let mut game = engine::new_headless_game_state();
+    let entity = create_ball(&mut game, 0.0);
+    engine::destroy_entity(&mut game, entity).expect("Failed destroying entity");
+
+    match engine::destroy_entity(&mut game, entity) {
+        Err(EngineError::DeadEntity(dead)) => assert_eq!(dead, entity),
+        result => panic!("Expected a dead entity error, got {:?}", result),
+    }
+}
+
+#[test]
+fn deleted_entities_lose_their_body_and_colliders() {
+    let mut game = engine::new_headless_game_state();
+    let entity = create_ball(&mut game, 0.0);
+    engine::step(&mut game, 1);
+
+    engine::world_mut(&mut game)
+        .delete_entity(entity)
+        .expect("Failed deleting entity");
+    engine::step(&mut game, 2);
+
+    assert_eq!(body_count(&game), 0);
+    assert_eq!(collider_count(&game), 0);
+}
+
+#[test]
+fn bodies_added_directly_are_kept() {
+    let mut game = engine::new_headless_game_state();
+    let handle = engine::world_mut(&mut game)
+        .write_resource::<MyBodySet>()
+        .0
+        .insert(RigidBodyDesc::new().build());
+    let entity = create_ball(&mut game, 0.0);
+    engine::world_mut(&mut game)
+        .delete_entity(entity)
+        .expect("Failed deleting entity");
+    engine::step(&mut game, 2);
+
+    let world = engine::world(&game);
+    let bodies = world.read_resource::<MyBodySet>();
+    assert!(bodies.0.get(handle).is_some());
+    assert_eq!(bodies.0.iter().count(), 1);
+}
+
+#[test]
+fn joints_are_removed_with_either_entity() {
+    let mut game = engine::new_headless_game_state();
+    let entity1 = create_ball(&mut game, 0.0);
+    let entity2 = create_ball(&mut game, 20.0);
+    let joint = engine::add_joint(
+        &mut game,
+        entity1,
+        entity2,
+        &JointDescriptor::Revolute {
+            anchor1: Point2::new(10.0, 0.0),
+            anchor2: Point2::new(-10.0, 0.0),
+        },
+    )
+    .expect("Failed adding joint");
+    engine::step(&mut game, 1);
+    assert_eq!(joint_count(&game), 1);
+
+    engine::world_mut(&mut game)
+        .delete_entity(entity1)
+        .expect("Failed deleting entity");
+    // The joint entity is deleted lazily, so it's only gone after the next tick
+    engine::step(&mut game, 2);
+
+    assert!(!engine::world(&game).is_alive(joint));
+    assert_eq!(joint_count(&game), 0);
+    assert!(engine::world(&game).is_alive(entity2));
+}
+
+#[test]
+fn destroying_the_joint_entity_removes_the_joint() {
+    let mut game = engine::new_headless_game_state();
+    let entity1 = create_ball(&mut game, 0.0);
+    let entity2 = create_ball(&mut game, 20.0);
+    let joint = engine::add_joint(
+        &mut game,
+        entity1,
+        entity2,
+        &JointDescriptor::Fixed {
+            anchor1: Point2::origin(),
+            anchor2: Point2::new(-20.0, 0.0),
+        },
+    )
+    .expect("Failed adding joint");
+
+    engine::destroy_entity(&mut game, joint).expect("Failed destroying joint");
+    engine::step(&mut game, 1);
+
+    assert_eq!(joint_count(&game), 0);
+    assert_eq!(body_count(&game), 2);
+}
+
+#[test]
+fn deleting_the_joint_entity_removes_the_joint() {
+    let mut game = engine::new_headless_game_state();
+    let entity1 = create_ball(&mut game, 0.0);
+    let entity2 = create_ball(&mut game, 20.0);
+    let joint = engine::add_joint(
+        &mut game,
+        entity1,
+        entity2,
+        &JointDescriptor::Fixed {
+            anchor1: Point2::origin(),
+            anchor2: Point2::new(-20.0, 0.0),
+        },
+    )
+    .expect("Failed adding joint");
+    engine::step(&mut game, 1);
+    assert_eq!(joint_count(&game), 1);
+
+    engine::world_mut(&mut game)
+        .delete_entity(joint)
+        .expect("Failed deleting joint");
+    engine::step(&mut game, 1);
+
+    assert_eq!(joint_count(&game), 0);
+    assert_eq!(body_count(&game), 2);
+}
+
+#[test]
+fn force_generators_are_removed_with_their_entities() {
+    let mut game = engine::new_headless_game_state();
+    let entity1 = create_ball(&mut game, 0.0);
+    let entity2 = create_ball(&mut game, 20.0);
+    let attractor = create_ball(&mut game, 100.0);
+    engine::add_spring(&mut game, entity1, entity2, &spring()).expect("Failed adding spring");
+    engine::add_attractor(&mut game, attractor, 10.0, 50.0).expect("Failed adding attractor");
+    engine::add_wind_zone(
+        &mut game,
+        attractor,
+        Vector2::new(10.0, 10.0),
+        Vector2::new(1.0, 0.0),
+    )
+    .expect("Failed adding wind zone");
+    engine::step(&mut game, 1);
+    assert_eq!(force_generator_count(&game), 3);
+
+    engine::world_mut(&mut game)
+        .delete_entity(entity2)
+        .expect("Failed deleting entity");
+    engine::step(&mut game, 1);
+    assert_eq!(force_generator_count(&game), 2);
+
+    engine::destroy_entity(&mut game, attractor).expect("Failed destroying entity");
+    engine::step(&mut game, 1);
+    assert_eq!(force_generator_count(&game), 0);
+}
@@ -0,0 +1,42 @@
+use engine::resources::FixedTimestep;
+
+const TOLERANCE: f64 = 1e-9;
+
+#[test]
+fn whole_steps_are_consumed() {
+    let mut timestep = FixedTimestep::new(10.0);
+    assert_eq!(timestep.advance(0.35), 3);
+    assert!((timestep.accumulator - 0.05).abs() < TOLERANCE);
+}
+
+#[test]
+fn remaining_time_carries_over_to_the_next_frame() {
+    let mut timestep = FixedTimestep::new(10.0);
+    assert_eq!(timestep.advance(0.06), 0);
+    assert_eq!(timestep.advance(0.06), 1);
+    assert!((timestep.accumulator - 0.02).abs() < TOLERANCE);
+}
+
+#[test]
+fn ticks_per_frame_are_limited() {
+    let mut timestep = FixedTimestep::new(10.0);
+    timestep.max_ticks_per_frame = 4;
+
+    assert_eq!(timestep.advance(10.0), 4);
+    // The time beyond the limit is dropped instead of being caught up on in later frames
+    assert!(timestep.accumulator <= timestep.step);
+    assert!(timestep.advance(0.0) <= 1);
+}
+
+#[test]
+fn alpha_is_the_fraction_of_a_step_left() {
+    let mut timestep = FixedTimestep::new(10.0);
+    assert_eq!(timestep.alpha(), 0.0);
+
+    timestep.advance(0.125);
+    assert!((timestep.alpha() - 0.25).abs() < TOLERANCE);
+
+    timestep.max_ticks_per_frame = 1;
+    timestep.advance(10.0);
+    assert!(timestep.alpha() <= 1.0);
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use common::*;
+use engine::physics::body::BodyDescriptor;
+use engine::physics::forces::SpringDescriptor;
+use engine::*;
+use nalgebra::{Point2, Vector2};
+use specs::WorldExt;
+
+const TOLERANCE: f64 = 1.0;
+
+#[test]
+fn gravity_is_scaled_per_body() {
+    let mut game = engine::new_headless_game_state();
+    assert_eq!(engine::gravity(&game), Vector2::zeros());
+    engine::set_gravity(&mut game, Vector2::new(0.0, 100.0));
+    assert_eq!(engine::gravity(&game), Vector2::new(0.0, 100.0));
+
+    let normal = create_undamped(&mut game, 0.0, 0.0);
+    let descriptor = BodyDescriptor::new().damping(0.0, 0.0);
+    let floating =
+        engine::create_entity_with(&mut game, 100.0, 0.0, 0.0, &descriptor.gravity_scale(0.0))
+            .build();
+    let heavy =
+        engine::create_entity_with(&mut game, 200.0, 0.0, 0.0, &descriptor.gravity_scale(2.0))
+            .build();
+
+    engine::step(&mut game, 60);
+
+    let fallen = position(&game, normal).y;
+    assert!(fallen > TOLERANCE);
+    assert_eq!(position(&game, floating), Vector2::new(100.0, 0.0));
+    assert!((position(&game, heavy).y - fallen * 2.0).abs() < TOLERANCE);
+}
+
+#[test]
+fn attractors_pull_bodies_within_their_radius() {
+    let mut game = engine::new_headless_game_state();
+    let source = create_fixed(&mut game, 0.0, 0.0);
+    let near = create_undamped(&mut game, 50.0, 0.0);
+    let far = create_undamped(&mut game, 200.0, 0.0);
+    engine::add_attractor(&mut game, source, 100.0, 100.0).expect("Failed adding attractor");
+
+    engine::step(&mut game, 10);
+
+    assert!(position(&game, near).x < 50.0);
+    assert!(position(&game, near).y.abs() < 1e-6);
+    assert_eq!(position(&game, far), Vector2::new(200.0, 0.0));
+    assert_eq!(position(&game, source), Vector2::zeros());
+}
+
+#[test]
+fn negative_attractors_push_bodies_away() {
+    let mut game = engine::new_headless_game_state();
+    let source = create_fixed(&mut game, 0.0, 0.0);
+    let near = create_undamped(&mut game, 0.0, 50.0);
+    engine::add_attractor(&mut game, source, -100.0, 100.0).expect("Failed adding attractor");
+
+    engine::step(&mut game, 10);
+
+    assert!(position(&game, near).y > 50.0);
+}
+
+#[test]
+fn wind_zones_accelerate_bodies_inside_them() {
+    let mut game = engine::new_headless_game_state();
+    let source = create_fixed(&mut game, 0.0, 0.0);
+    let inside = create_undamped(&mut game, -40.0, 0.0);
+    let outside = create_undamped(&mut game, 0.0, 100.0);
+    engine::add_wind_zone(
+        &mut game,
+        source,
+        Vector2::new(50.0, 50.0),
+        Vector2::new(100.0, 0.0),
+    )
+    .expect("Failed adding wind zone");
+
+    engine::step(&mut game, 10);
+
+    assert!(position(&game, inside).x > -40.0);
+    assert_eq!(position(&game, outside), Vector2::new(0.0, 100.0));
+}
+
+#[test]
+fn springs_pull_bodies_to_their_length() {
+    let mut game = engine::new_headless_game_state();
+    let body1 = create_undamped(&mut game, 0.0, 0.0);
+    let body2 = create_undamped(&mut game, 100.0, 0.0);
+    let spring = SpringDescriptor {
+        anchor1: Point2::origin(),
+        anchor2: Point2::origin(),
+        length: 50.0,
+        stiffness: 10.0,
+        damping: 4.0,
+    };
+    engine::add_spring(&mut game, body1, body2, &spring).expect("Failed adding spring");
+
+    engine::step(&mut game, 10);
+    assert!((position(&game, body2) - position(&game, body1)).norm() < 100.0);
+
+    engine::step(&mut game, 600);
+    let distance = (position(&game, body2) - position(&game, body1)).norm();
+    assert!((distance - 50.0).abs() < TOLERANCE);
+    // The spring pulls both ends equally, so their center stays put
+    let center = (position(&game, body1) + position(&game, body2)) / 2.0;
+    assert!((center - Vector2::new(50.0, 0.0)).norm() < TOLERANCE);
+}
+
+#[test]
+fn force_fields_need_a_body() {
+    let mut game = engine::new_headless_game_state();
+    let bodiless = engine::world_mut(&mut game).create_entity().build();
+
+    assert!(engine::add_attractor(&mut game, bodiless, 1.0, 1.0).is_err());
+}
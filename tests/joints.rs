@@ -0,0 +1,105 @@
+mod common;
+
+use common::*;
+use engine::physics::body::BodyDescriptor;
+use engine::physics::collider::ColliderDescriptor;
+use engine::physics::joint::JointDescriptor;
+use engine::*;
+use nalgebra::{Point2, Vector2};
+use ncollide2d::shape::{Ball, ShapeHandle};
+use specs::WorldExt;
+
+const TOLERANCE: f64 = 1.0;
+
+fn rope(length: f64) -> JointDescriptor {
+    JointDescriptor::Rope {
+        anchor1: Point2::origin(),
+        anchor2: Point2::origin(),
+        length,
+    }
+}
+
+#[test]
+fn rope_holds_a_hanging_weight() {
+    let mut game = engine::new_headless_game_state();
+    engine::set_gravity(&mut game, Vector2::new(0.0, 200.0));
+    let anchor = create_fixed(&mut game, 0.0, 0.0);
+    let weight = create_undamped(&mut game, 0.0, 0.0);
+    engine::add_joint(&mut game, anchor, weight, &rope(50.0)).expect("Failed adding rope");
+
+    for _ in 0..120 {
+        engine::step(&mut game, 1);
+        assert!(position(&game, weight).norm() < 50.0 + TOLERANCE);
+    }
+    assert!((position(&game, weight) - Vector2::new(0.0, 50.0)).norm() < TOLERANCE);
+}
+
+#[test]
+fn slack_rope_does_not_pull() {
+    let mut game = engine::new_headless_game_state();
+    let anchor = create_fixed(&mut game, 0.0, 0.0);
+    let weight = create_undamped(&mut game, 30.0, 0.0);
+    engine::add_joint(&mut game, anchor, weight, &rope(50.0)).expect("Failed adding rope");
+
+    engine::step(&mut game, 60);
+
+    assert_eq!(position(&game, weight), Vector2::new(30.0, 0.0));
+}
+
+#[test]
+fn revolute_joint_swings_around_its_anchor() {
+    let mut game = engine::new_headless_game_state();
+    engine::set_gravity(&mut game, Vector2::new(0.0, 200.0));
+    let anchor = create_fixed(&mut game, 0.0, 0.0);
+    let weight = create_undamped(&mut game, 30.0, 0.0);
+    // Without a collider the weight has no angular inertia and couldn't turn while swinging
+    engine::add_collider_with(
+        &mut game,
+        weight,
+        &ColliderDescriptor::new(ShapeHandle::new(Ball::new(5.0))).density(1.0),
+    )
+    .expect("Failed adding collider");
+    engine::add_joint(
+        &mut game,
+        anchor,
+        weight,
+        &JointDescriptor::Revolute {
+            anchor1: Point2::origin(),
+            anchor2: Point2::new(-30.0, 0.0),
+        },
+    )
+    .expect("Failed adding joint");
+
+    engine::step(&mut game, 30);
+
+    let swung = position(&game, weight);
+    assert!(swung.y > TOLERANCE);
+    assert!((swung.norm() - 30.0).abs() < TOLERANCE);
+}
+
+#[test]
+fn joints_need_bodies() {
+    let mut game = engine::new_headless_game_state();
+    let anchor = create_fixed(&mut game, 0.0, 0.0);
+    let bodiless = engine::world_mut(&mut game).create_entity().build();
+
+    assert!(engine::add_joint(&mut game, anchor, bodiless, &rope(10.0)).is_err());
+}
+
+#[test]
+fn dragged_bodies_follow_the_anchor() {
+    let mut game = engine::new_headless_game_state();
+    let descriptor = BodyDescriptor::new().damping(5.0, 5.0);
+    let dragged = engine::create_entity_with(&mut game, 0.0, 0.0, 0.0, &descriptor).build();
+    let anchor = engine::start_drag(&mut game, dragged, Point2::origin(), 1.0e6)
+        .expect("Failed starting drag");
+
+    engine::move_drag(&mut game, anchor, Point2::new(100.0, 0.0));
+    engine::step(&mut game, 120);
+    assert!((position(&game, dragged) - Vector2::new(100.0, 0.0)).norm() < TOLERANCE);
+
+    engine::destroy_entity(&mut game, anchor).expect("Failed letting go");
+    engine::move_drag(&mut game, anchor, Point2::new(-100.0, 0.0));
+    engine::step(&mut game, 60);
+    assert!(position(&game, dragged).x > 50.0);
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::*;
+use engine::physics::body::BodyDescriptor;
+use engine::physics::events::CollisionEvent;
+use engine::*;
+use ggez::event::KeyCode;
+use nalgebra::Vector2;
+use specs::shrev::EventChannel;
+use specs::WorldExt;
+
+#[test]
+fn player_moves_in_the_direction_held() {
+    let mut game = engine::new_headless_game_state();
+    let player = create_player(&mut game, 0.0, 0.0);
+
+    set_key(&mut game, KeyCode::D, true);
+    engine::step(&mut game, 30);
+    let moved = position(&game, player);
+    assert!(moved.x > 1.0);
+    assert!(moved.y.abs() < 1e-6);
+
+    set_key(&mut game, KeyCode::D, false);
+    set_key(&mut game, KeyCode::W, true);
+    engine::step(&mut game, 30);
+    let moved_north = position(&game, player);
+    assert!(moved_north.y < moved.y - 1.0);
+}
+
+#[test]
+fn player_stops_without_input() {
+    let mut game = engine::new_headless_game_state();
+    let player = create_player(&mut game, 0.0, 0.0);
+    set_key(&mut game, KeyCode::D, true);
+    engine::step(&mut game, 10);
+
+    set_key(&mut game, KeyCode::D, false);
+    engine::step(&mut game, 10);
+    let stopped = position(&game, player);
+    engine::step(&mut game, 10);
+
+    assert!((position(&game, player) - stopped).norm() < 1e-3);
+}
+
+#[test]
+fn player_is_stopped_by_walls_and_reports_the_contact() {
+    let mut game = engine::new_headless_game_state();
+    let player = create_player(&mut game, 0.0, 0.0);
+    let wall = create_box(&mut game, 25.0, 0.0, &BodyDescriptor::fixed());
+    let mut reader = engine::world_mut(&mut game)
+        .write_resource::<EventChannel<CollisionEvent>>()
+        .register_reader();
+
+    set_key(&mut game, KeyCode::D, true);
+    engine::step(&mut game, 120);
+
+    assert!(position(&game, player).x < 25.0 - HALF_EXTENT * 2.0 + 0.5);
+    assert_eq!(position(&game, wall), Vector2::new(25.0, 0.0));
+    let world = engine::world(&game);
+    let started = world
+        .read_resource::<EventChannel<CollisionEvent>>()
+        .read(&mut reader)
+        .any(|event| match *event {
+            CollisionEvent::ContactStarted(entity1, entity2) => {
+                (entity1, entity2) == (player, wall) || (entity1, entity2) == (wall, player)
+            }
+            _ => false,
+        });
+    assert!(started);
+}
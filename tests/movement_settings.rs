@@ -0,0 +1,92 @@
+use engine::resources::{ActionContext, OppositeInputPolicy, PlayerAction};
+use nalgebra::Vector2;
+
+const TOLERANCE: f64 = 1e-9;
+
+fn context(policy: OppositeInputPolicy) -> ActionContext {
+    let mut context = ActionContext::new();
+    context.movement_settings.opposite_input_policy = policy;
+    context
+}
+
+fn assert_movement(context: &ActionContext, expected: Vector2<f64>) {
+    let movement = context.movement();
+    assert!(
+        (movement - expected).norm() < TOLERANCE,
+        "Expected movement {:?}, got {:?}",
+        expected,
+        movement
+    );
+}
+
+#[test]
+fn last_pressed_direction_wins() {
+    let mut context = context(OppositeInputPolicy::LastPressedWins);
+    context.set_action(PlayerAction::MoveEast, 1.0);
+    context.set_action(PlayerAction::MoveWest, 1.0);
+    assert_movement(&context, Vector2::new(-1.0, 0.0));
+
+    context.set_action(PlayerAction::MoveEast, 0.0);
+    context.set_action(PlayerAction::MoveEast, 1.0);
+    assert_movement(&context, Vector2::new(1.0, 0.0));
+
+    context.set_action(PlayerAction::MoveEast, 0.0);
+    assert_movement(&context, Vector2::new(-1.0, 0.0));
+}
+
+#[test]
+fn opposite_directions_cancel_out() {
+    let mut context = context(OppositeInputPolicy::Cancel);
+    context.set_action(PlayerAction::MoveNorth, 1.0);
+    context.set_action(PlayerAction::MoveSouth, 1.0);
+    assert_movement(&context, Vector2::zeros());
+
+    context.set_action(PlayerAction::MoveNorth, 0.3);
+    assert_movement(&context, Vector2::new(0.0, 0.7 - 0.1) / 0.9);
+}
+
+#[test]
+fn movement_within_the_dead_zone_is_ignored() {
+    let mut context = ActionContext::new();
+    context.movement_settings.dead_zone = 0.1;
+    context.set_action(PlayerAction::MoveEast, 0.05);
+    assert_movement(&context, Vector2::zeros());
+
+    context.set_action(PlayerAction::MoveEast, 0.55);
+    assert_movement(&context, Vector2::new(0.5, 0.0));
+
+    context.set_action(PlayerAction::MoveEast, 1.0);
+    assert_movement(&context, Vector2::new(1.0, 0.0));
+}
+
+#[test]
+fn dead_zone_is_clamped() {
+    let mut context = ActionContext::new();
+    context.movement_settings.dead_zone = -0.5;
+    context.set_action(PlayerAction::MoveEast, 0.3);
+    assert_movement(&context, Vector2::new(0.3, 0.0));
+
+    context.movement_settings.dead_zone = 1.0;
+    context.set_action(PlayerAction::MoveEast, 1.0);
+    assert_movement(&context, Vector2::new(1.0, 0.0));
+
+    context.movement_settings.dead_zone = 2.0;
+    assert!(context.movement().x.is_finite());
+}
+
+#[test]
+fn diagonal_movement_is_normalized() {
+    let mut context = ActionContext::new();
+    context.set_action(PlayerAction::MoveEast, 1.0);
+    context.set_action(PlayerAction::MoveSouth, 1.0);
+    assert_movement(&context, Vector2::new(1.0, 1.0) / 2f64.sqrt());
+}
+
+#[test]
+fn diagonal_movement_keeps_its_length_without_normalizing() {
+    let mut context = ActionContext::new();
+    context.movement_settings.normalize = false;
+    context.set_action(PlayerAction::MoveEast, 1.0);
+    context.set_action(PlayerAction::MoveSouth, 1.0);
+    assert_movement(&context, Vector2::new(1.0, 1.0));
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::*;
+use engine::physics::body::BodyDescriptor;
+use engine::*;
+use nalgebra::Vector2;
+
+const TOLERANCE: f64 = 0.1;
+
+/// How far two boxes of the test size overlap on the x axis.
+fn overlap(game: &GameState, first: Entity, second: Entity) -> f64 {
+    let distance = (position(game, first).x - position(game, second).x).abs();
+    HALF_EXTENT * 2.0 - distance
+}
+
+#[test]
+fn overlapping_dynamic_bodies_are_pushed_apart() {
+    let mut game = engine::new_headless_game_state();
+    let descriptor = BodyDescriptor::new().damping(0.0, 0.0);
+    let first = create_box(&mut game, 0.0, 0.0, &descriptor);
+    let second = create_box(&mut game, 15.0, 0.0, &descriptor);
+
+    engine::step(&mut game, 10);
+
+    assert!(overlap(&game, first, second) < TOLERANCE);
+}
+
+#[test]
+fn moving_body_does_not_get_stuck_in_body_with_velocity() {
+    // The known issue: a moving entity running into a non-moving entity that still has a
+    // velocity, with neither moving out of the collision
+    let mut game = engine::new_headless_game_state();
+    let descriptor = BodyDescriptor::new();
+    let mover = create_box(&mut game, 0.0, 0.0, &descriptor);
+    let resting = create_box(&mut game, 19.0, 0.0, &descriptor);
+
+    for _ in 0..30 {
+        set_velocity(&mut game, mover, Vector2::new(300.0, 0.0));
+        set_velocity(&mut game, resting, Vector2::new(-1.0, 0.0));
+        engine::step(&mut game, 1);
+        assert!(overlap(&game, mover, resting) < TOLERANCE);
+    }
+
+    for _ in 0..30 {
+        set_velocity(&mut game, mover, Vector2::new(-300.0, 0.0));
+        engine::step(&mut game, 1);
+    }
+    assert!(overlap(&game, mover, resting) < 0.0);
+}
+
+#[test]
+fn static_bodies_are_not_pushed() {
+    let mut game = engine::new_headless_game_state();
+    let wall = create_box(&mut game, 15.0, 0.0, &BodyDescriptor::fixed());
+    let mover = create_box(&mut game, 0.0, 0.0, &BodyDescriptor::new());
+
+    for _ in 0..30 {
+        set_velocity(&mut game, mover, Vector2::new(300.0, 0.0));
+        engine::step(&mut game, 1);
+    }
+
+    assert_eq!(position(&game, wall), Vector2::new(15.0, 0.0));
+    assert!(overlap(&game, mover, wall) < TOLERANCE);
+}
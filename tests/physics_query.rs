@@ -0,0 +1,153 @@
+mod common;
+
+use common::*;
+use engine::physics::body::BodyDescriptor;
+use engine::physics::query::{PhysicsQuery, QueryFilter};
+use engine::*;
+use nalgebra::{Isometry2, Point2, Vector2};
+use ncollide2d::shape::Ball;
+
+const TOLERANCE: f64 = 1e-3;
+
+/// Two boxes along the x axis, the nearest first. Queries see colliders as of the last step.
+fn create_row(game: &mut GameState) -> (Entity, Entity) {
+    let near = create_box(game, 50.0, 0.0, &BodyDescriptor::fixed());
+    let far = create_box(game, 100.0, 0.0, &BodyDescriptor::fixed());
+    engine::step(game, 1);
+    (near, far)
+}
+
+#[test]
+fn rays_hit_the_nearest_entity() {
+    let mut game = engine::new_headless_game_state();
+    let (near, far) = create_row(&mut game);
+    let query = engine::world(&game).system_data::<PhysicsQuery>();
+
+    let hit = query
+        .cast_ray(
+            Point2::origin(),
+            Vector2::new(1.0, 0.0),
+            1000.0,
+            &QueryFilter::new(),
+        )
+        .expect("Ray missed");
+    assert_eq!(hit.entity, near);
+    assert!((hit.toi - 40.0).abs() < TOLERANCE);
+    assert!((hit.point - Point2::new(40.0, 0.0)).norm() < TOLERANCE);
+    assert!((hit.normal - Vector2::new(-1.0, 0.0)).norm() < TOLERANCE);
+
+    let hits = query.cast_ray_all(
+        Point2::origin(),
+        Vector2::new(1.0, 0.0),
+        1000.0,
+        &QueryFilter::new(),
+    );
+    let entities: Vec<Entity> = hits.iter().map(|hit| hit.entity).collect();
+    assert_eq!(entities, vec![near, far]);
+}
+
+#[test]
+fn rays_stop_at_their_length() {
+    let mut game = engine::new_headless_game_state();
+    create_row(&mut game);
+    let query = engine::world(&game).system_data::<PhysicsQuery>();
+
+    let hit = query.cast_ray(
+        Point2::origin(),
+        Vector2::new(1.0, 0.0),
+        30.0,
+        &QueryFilter::new(),
+    );
+    assert_eq!(hit, None);
+}
+
+#[test]
+fn excluded_entities_are_not_hit() {
+    let mut game = engine::new_headless_game_state();
+    let (near, far) = create_row(&mut game);
+    let query = engine::world(&game).system_data::<PhysicsQuery>();
+
+    let hit = query
+        .cast_ray(
+            Point2::origin(),
+            Vector2::new(1.0, 0.0),
+            1000.0,
+            &QueryFilter::new().excluding(near),
+        )
+        .expect("Ray missed");
+    assert_eq!(hit.entity, far);
+}
+
+#[test]
+fn sensors_are_only_found_when_included() {
+    let mut game = engine::new_headless_game_state();
+    let sensor =
+        engine::create_entity_with(&mut game, 50.0, 0.0, 0.0, &BodyDescriptor::fixed()).build();
+    engine::add_trigger(&mut game, sensor, box_shape()).expect("Failed adding trigger");
+    engine::step(&mut game, 1);
+    let query = engine::world(&game).system_data::<PhysicsQuery>();
+
+    let point = Point2::new(50.0, 0.0);
+    assert!(query
+        .entities_at_point(&point, &QueryFilter::new())
+        .is_empty());
+    assert_eq!(
+        query.entities_at_point(&point, &QueryFilter::new().include_sensors(true)),
+        vec![sensor]
+    );
+}
+
+#[test]
+fn entities_are_found_by_point_and_box() {
+    let mut game = engine::new_headless_game_state();
+    let (near, far) = create_row(&mut game);
+    let query = engine::world(&game).system_data::<PhysicsQuery>();
+    let filter = QueryFilter::new();
+
+    assert_eq!(
+        query.entities_at_point(&Point2::new(55.0, 5.0), &filter),
+        vec![near]
+    );
+    assert!(query
+        .entities_at_point(&Point2::new(75.0, 0.0), &filter)
+        .is_empty());
+
+    let mut found =
+        query.entities_in_aabb(Point2::new(55.0, -5.0), Point2::new(95.0, 5.0), &filter);
+    found.sort();
+    let mut expected = vec![near, far];
+    expected.sort();
+    assert_eq!(found, expected);
+    assert!(query
+        .entities_in_aabb(Point2::new(65.0, -5.0), Point2::new(85.0, 5.0), &filter)
+        .is_empty());
+}
+
+#[test]
+fn shapes_are_cast_until_they_touch() {
+    let mut game = engine::new_headless_game_state();
+    let (near, _) = create_row(&mut game);
+    let query = engine::world(&game).system_data::<PhysicsQuery>();
+
+    let hit = query
+        .cast_shape(
+            &Ball::new(5.0),
+            &Isometry2::identity(),
+            &Vector2::new(1.0, 0.0),
+            1000.0,
+            &QueryFilter::new(),
+        )
+        .expect("Shape missed");
+    assert_eq!(hit.entity, near);
+    assert!((hit.toi - 35.0).abs() < TOLERANCE);
+    assert!((hit.normal - Vector2::new(-1.0, 0.0)).norm() < TOLERANCE);
+
+    let miss = query.cast_shape(
+        &Ball::new(5.0),
+        &Isometry2::translation(0.0, 50.0),
+        &Vector2::new(1.0, 0.0),
+        1000.0,
+        &QueryFilter::new(),
+    );
+    assert_eq!(miss, None);
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::*;
+use engine::error::EngineError;
+use engine::resources::{Camera, InputContext};
+use engine::*;
+use ggez::event::KeyCode;
+use nalgebra::Point2;
+use specs::WorldExt;
+
+#[test]
+fn replays_move_the_player_like_the_recording() {
+    let path = std::env::temp_dir().join("engine_replay_test.ron");
+    let mut game = engine::new_headless_game_state();
+    let player = create_player(&mut game, 0.0, 0.0);
+    engine::start_recording(&mut game);
+    set_key(&mut game, KeyCode::D, true);
+    engine::step(&mut game, 20);
+    set_key(&mut game, KeyCode::W, true);
+    engine::step(&mut game, 20);
+    set_key(&mut game, KeyCode::D, false);
+    set_key(&mut game, KeyCode::W, false);
+    engine::step(&mut game, 10);
+    engine::save_recording(&mut game, &path).expect("Failed saving recording");
+    let recorded = position(&game, player);
+
+    let mut replayed_game = engine::new_headless_game_state();
+    let replayed_player = create_player(&mut replayed_game, 0.0, 0.0);
+    engine::play_recording(&mut replayed_game, &path).expect("Failed loading recording");
+    engine::step(&mut replayed_game, 50);
+    let _ = std::fs::remove_file(&path);
+
+    assert!(recorded.x > 1.0);
+    assert_eq!(position(&replayed_game, replayed_player), recorded);
+}
+
+fn mouse_world_position(game: &GameState) -> (f64, f64) {
+    engine::world(game)
+        .read_resource::<InputContext>()
+        .mouse_world_position
+}
+
+#[test]
+fn replays_use_the_recorded_mouse_world_position() {
+    let path = std::env::temp_dir().join("engine_replay_mouse_test.ron");
+    let mut game = engine::new_headless_game_state();
+    engine::world_mut(&mut game)
+        .write_resource::<InputContext>()
+        .mouse_position = (10.0, 20.0);
+    engine::start_recording(&mut game);
+    engine::step(&mut game, 1);
+    engine::save_recording(&mut game, &path).expect("Failed saving recording");
+    let recorded = mouse_world_position(&game);
+
+    // The camera moves with the frames drawn, so it may be elsewhere during the replay
+    let mut replayed_game = engine::new_headless_game_state();
+    engine::world_mut(&mut replayed_game)
+        .write_resource::<Camera>()
+        .position = Point2::new(500.0, 500.0);
+    engine::play_recording(&mut replayed_game, &path).expect("Failed loading recording");
+    engine::step(&mut replayed_game, 1);
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(mouse_world_position(&replayed_game), recorded);
+}
+
+#[test]
+fn saving_without_recording_fails() {
+    let mut game = engine::new_headless_game_state();
+    let path = std::env::temp_dir().join("engine_replay_not_recording.ron");
+    match engine::save_recording(&mut game, &path) {
+        Err(EngineError::NotRecording) => {}
+        result => panic!("Expected a not recording error, got {:?}", result),
+    }
+}
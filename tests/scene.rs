@@ -0,0 +1,80 @@
+use engine::components::ColliderComponent;
+use engine::scene::{self, Scene};
+use specs::WorldExt;
+
+/// A scene saved before entities could have more than one collider.
+const SINGLE_COLLIDER_SCENE: &str = r#"(
+    entities: [
+        (
+            position: (10.0, 20.0),
+            collider: Some((
+                shape: Cuboid(half_extents: (5.0, 5.0)),
+                sensor: true,
+            )),
+        ),
+        (
+            position: (0.0, 0.0),
+        ),
+    ],
+)"#;
+
+#[test]
+fn single_collider_is_read_into_colliders() {
+    let scene: Scene = ron::de::from_str(SINGLE_COLLIDER_SCENE).expect("Failed reading scene");
+
+    assert_eq!(scene.entities[0].colliders.len(), 1);
+    assert!(scene.entities[0].colliders[0].sensor);
+    assert!(scene.entities[1].colliders.is_empty());
+}
+
+#[test]
+fn single_collider_is_instantiated() {
+    let scene: Scene = ron::de::from_str(SINGLE_COLLIDER_SCENE).expect("Failed reading scene");
+    let mut game = engine::new_headless_game_state();
+
+    let entities = scene::instantiate_scene(&mut game, &scene);
+
+    let world = engine::world(&game);
+    let collider_components = world.read_component::<ColliderComponent>();
+    let collider_component = collider_components
+        .get(entities[0])
+        .expect("Entity has no colliders");
+    assert_eq!(collider_component.0.len(), 1);
+}
+
+#[test]
+fn saved_scenes_only_have_colliders() {
+    let scene: Scene = ron::de::from_str(SINGLE_COLLIDER_SCENE).expect("Failed reading scene");
+
+    let saved = ron::ser::to_string(&scene).expect("Failed saving scene");
+
+    assert!(saved.contains("colliders"));
+    assert!(!saved.contains("collider:"));
+}
+
+#[test]
+fn body_mass_does_not_grow_with_collider_density() {
+    let scene: Scene = ron::de::from_str(
+        r#"(
+            entities: [
+                (
+                    position: (0.0, 0.0),
+                    body: (mass: 2.0),
+                    colliders: [(shape: Ball(radius: 5.0), density: 1.0)],
+                ),
+            ],
+        )"#,
+    )
+    .expect("Failed reading scene");
+    let mut game = engine::new_headless_game_state();
+    scene::instantiate_scene(&mut game, &scene);
+    engine::step(&mut game, 1);
+
+    for _ in 0..3 {
+        let captured = scene::capture_scene(&game);
+        assert_eq!(captured.entities[0].body.mass, 2.0);
+        game = engine::new_headless_game_state();
+        scene::instantiate_scene(&mut game, &captured);
+        engine::step(&mut game, 1);
+    }
+}
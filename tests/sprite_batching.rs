@@ -0,0 +1,51 @@
+use engine::assets::PLACEHOLDER_PATH;
+use engine::systems::draw_system::{batch_ranges, SpriteBatchKey};
+use engine::FilterMode;
+
+fn key(texture: &str) -> SpriteBatchKey {
+    SpriteBatchKey {
+        texture,
+        filter: FilterMode::Linear,
+    }
+}
+
+#[test]
+fn sprites_sharing_a_texture_are_batched_together() {
+    let keys = [key("/bullet.png"), key("/bullet.png"), key("/bullet.png")];
+    assert_eq!(batch_ranges(&keys), vec![0..3]);
+}
+
+#[test]
+fn batches_follow_draw_order() {
+    // The tile drawn between the bullets must stay between them
+    let keys = [
+        key("/bullet.png"),
+        key("/bullet.png"),
+        key("/tile.png"),
+        key("/bullet.png"),
+    ];
+    assert_eq!(batch_ranges(&keys), vec![0..2, 2..3, 3..4]);
+}
+
+#[test]
+fn different_filters_are_batched_separately() {
+    let nearest = SpriteBatchKey {
+        texture: "/tile.png",
+        filter: FilterMode::Nearest,
+    };
+    let keys = [key("/tile.png"), nearest, nearest];
+    assert_eq!(batch_ranges(&keys), vec![0..1, 1..3]);
+}
+
+#[test]
+fn placeholders_are_batched_apart_from_the_image_they_replace() {
+    // A sprite of the sheet whose frame is missing holds the placeholder texture instead
+    let keys = [key("/sheet.png"), key(PLACEHOLDER_PATH), key("/sheet.png")];
+    assert_eq!(batch_ranges(&keys), vec![0..1, 1..2, 2..3]);
+}
+
+#[test]
+fn no_sprites_make_no_batches() {
+    let keys: Vec<SpriteBatchKey> = Vec::new();
+    assert!(batch_ranges(&keys).is_empty());
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::*;
+use engine::physics::events::CollisionEvent;
+use engine::*;
+use nalgebra::Vector2;
+use specs::shrev::EventChannel;
+use specs::WorldExt;
+
+#[test]
+fn bodies_pass_through_triggers_and_report_the_overlap() {
+    let mut game = engine::new_headless_game_state();
+    let trigger = create_fixed(&mut game, 0.0, 0.0);
+    engine::add_trigger(&mut game, trigger, box_shape()).expect("Failed adding trigger");
+    let mover = create_undamped(&mut game, -60.0, 0.0);
+    add_box(&mut game, mover);
+    let mut reader = engine::world_mut(&mut game)
+        .write_resource::<EventChannel<CollisionEvent>>()
+        .register_reader();
+
+    set_velocity(&mut game, mover, Vector2::new(300.0, 0.0));
+    engine::step(&mut game, 60);
+
+    assert!(position(&game, mover).x > 60.0);
+    assert!((velocity(&game, mover) - Vector2::new(300.0, 0.0)).norm() < 1e-9);
+    let is_pair = |entity1, entity2| {
+        (entity1, entity2) == (trigger, mover) || (entity1, entity2) == (mover, trigger)
+    };
+    let world = engine::world(&game);
+    let events: Vec<CollisionEvent> = world
+        .read_resource::<EventChannel<CollisionEvent>>()
+        .read(&mut reader)
+        .cloned()
+        .collect();
+    let entered = events.iter().position(|event| match *event {
+        CollisionEvent::ProximityEntered(entity1, entity2) => is_pair(entity1, entity2),
+        _ => false,
+    });
+    let exited = events.iter().position(|event| match *event {
+        CollisionEvent::ProximityExited(entity1, entity2) => is_pair(entity1, entity2),
+        _ => false,
+    });
+    match (entered, exited) {
+        (Some(entered), Some(exited)) => assert!(entered < exited),
+        _ => panic!(
+            "Expected the mover to enter and exit the trigger, got {:?}",
+            events
+        ),
+    }
+    assert!(!events.iter().any(|event| match *event {
+        CollisionEvent::ContactStarted(..) => true,
+        _ => false,
+    }));
+}